@@ -1,6 +1,7 @@
 use crate::{
     evol::{
-        evol_coordinator::EvolutionCoordinator, rand::RandomNumberGenerator, traits::Phenotype,
+        evol_coordinator::EvolutionCoordinator, mutation_schedule::MutationSchedule,
+        rand::RandomNumberGenerator, traits::Phenotype,
     },
     math2d::{circle::Circle, point::Point, vector::Vector},
 };
@@ -87,13 +88,18 @@ impl Phenotype for BubbleCircle {
     }
 
     fn mutate(&mut self, rng: &mut RandomNumberGenerator, evol_coordinator: EvolutionCoordinator) {
+        let step_size = MutationSchedule::CosineAnnealing {
+            start: 5.0,
+            end: 0.5,
+        }
+        .step_size(&evol_coordinator) as f32;
         let random_mutation_value_x = rng
-            .fetch_uniform(-5.0, 5.0, 1)
+            .fetch_uniform(-step_size, step_size, 1)
             .back()
             .copied()
             .unwrap_or_default();
         let random_mutation_value_y = rng
-            .fetch_uniform(-5.0, 5.0, 1)
+            .fetch_uniform(-step_size, step_size, 1)
             .back()
             .copied()
             .unwrap_or_default();
@@ -110,6 +110,21 @@ impl Canvas {
         ret
     }
 
+    // like get_pixels, but shows the value of every pixel from 1 to 9 instead of X
+    pub fn get_heatmap(&self) -> String {
+        let mut ret = String::new();
+        for row in self.pixels.iter() {
+            for pixel in row.iter() {
+                ret.push(match *pixel {
+                    0 => '.',
+                    value => char::from_digit(value.clamp(1, 9) as u32, 10).unwrap(),
+                });
+            }
+            ret.push('\n');
+        }
+        ret
+    }
+
     fn draw_line(&mut self, line: &Line) {
         let mut start = line.get_start();
         let mut end = line.get_end();
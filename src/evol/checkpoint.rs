@@ -0,0 +1,176 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use super::{
+    evol_coordinator::EvolutionCoordinator, evol_launcher::Fitness, hall_of_fame::HallOfFameEntry,
+    rand::RandomNumberGenerator, traits::Phenotype,
+};
+
+// raised whenever a line of the checkpoint format changes, a checkpoint of another version is rejected
+// instead of being misread
+const CHECKPOINT_VERSION: u32 = 3;
+
+// a type that can be written to a single line of a checkpoint file and read back from it
+pub trait Checkpointable
+where
+    Self: Sized,
+{
+    fn to_checkpoint(&self) -> String;
+    fn from_checkpoint(line: &str) -> Option<Self>;
+}
+
+// the state of a run right after a generation has been scored and before its parents are selected
+pub struct Checkpoint<Pheno: Phenotype, EvolOptions> {
+    pub evol_coordinator: EvolutionCoordinator,
+    pub evol_options: EvolOptions,
+    pub rng: RandomNumberGenerator,
+    pub fitness: Vec<Fitness<Pheno>>,
+    pub hall_of_fame: Vec<HallOfFameEntry<Pheno>>,
+    pub novelty_archive: Vec<Vec<f64>>, // empty without a novelty search
+    pub strategy_state: Option<String>, // the save_state of the strategy, a strategy line per line
+}
+
+impl<Pheno, EvolOptions> Checkpoint<Pheno, EvolOptions>
+where
+    Pheno: Phenotype + Checkpointable,
+    EvolOptions: Checkpointable,
+{
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = format!("version {}\n", CHECKPOINT_VERSION);
+        content.push_str(&format!(
+            "coordinator {}\n",
+            self.evol_coordinator.to_checkpoint()
+        ));
+        content.push_str(&format!("options {}\n", self.evol_options.to_checkpoint()));
+        content.push_str(&format!("rng {}\n", self.rng.to_checkpoint()));
+        if let Some(strategy_state) = &self.strategy_state {
+            for line in strategy_state.split('\n') {
+                content.push_str(&format!("strategy {}\n", line));
+            }
+        }
+        for fit in self.fitness.iter() {
+            content.push_str(&format!(
+                "phenotype {} {} {}\n",
+                fit.score,
+                fit.ranking_score,
+                fit.phenotype.to_checkpoint()
+            ));
+        }
+        for behaviour in self.novelty_archive.iter() {
+            let values: Vec<String> = behaviour.iter().map(|value| value.to_string()).collect();
+            content.push_str(&format!("archive {}\n", values.join(" ")));
+        }
+        for entry in self.hall_of_fame.iter() {
+            content.push_str(&format!(
+                "fame {} {} {}\n",
+                entry.generation,
+                entry.score,
+                entry.phenotype.to_checkpoint()
+            ));
+        }
+        // a crash while writing must not destroy the previous checkpoint; the temporary file appends a
+        // suffix instead of replacing the extension, so it never is another file of the same stem
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        fs::write(&temporary_path, content)?;
+        fs::rename(&temporary_path, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut lines = content.lines();
+        let version = lines
+            .next()
+            .and_then(|line| line.strip_prefix("version "))
+            .and_then(|version| version.parse::<u32>().ok());
+        if version != Some(CHECKPOINT_VERSION) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "checkpoint {} is not of version {}",
+                    path.display(),
+                    CHECKPOINT_VERSION
+                ),
+            ));
+        }
+        let mut evol_coordinator = None;
+        let mut evol_options = None;
+        let mut rng = None;
+        let mut fitness = Vec::new();
+        let mut hall_of_fame = Vec::new();
+        let mut novelty_archive = Vec::new();
+        let mut strategy_lines = Vec::new();
+        for line in lines {
+            let (key, value) = line.split_once(' ').ok_or_else(|| invalid_line(line))?;
+            match key {
+                "coordinator" => evol_coordinator = EvolutionCoordinator::from_checkpoint(value),
+                "options" => evol_options = EvolOptions::from_checkpoint(value),
+                "rng" => rng = RandomNumberGenerator::from_checkpoint(value),
+                "strategy" => strategy_lines.push(value),
+                "phenotype" => {
+                    let mut values = value.splitn(3, ' ');
+                    let mut next_value = || values.next().ok_or_else(|| invalid_line(line));
+                    let score = next_value()?;
+                    let ranking_score = next_value()?;
+                    let phenotype = next_value()?;
+                    fitness.push(Fitness {
+                        phenotype: Pheno::from_checkpoint(phenotype)
+                            .ok_or_else(|| invalid_line(line))?,
+                        score: score.parse::<f64>().map_err(|_| invalid_line(line))?,
+                        ranking_score: ranking_score
+                            .parse::<f64>()
+                            .map_err(|_| invalid_line(line))?,
+                    });
+                }
+                "archive" => novelty_archive.push(
+                    value
+                        .split(' ')
+                        .filter(|token| !token.is_empty())
+                        .map(|token| token.parse::<f64>().map_err(|_| invalid_line(line)))
+                        .collect::<io::Result<Vec<f64>>>()?,
+                ),
+                "fame" => {
+                    let mut values = value.splitn(3, ' ');
+                    let mut next_value = || values.next().ok_or_else(|| invalid_line(line));
+                    let generation = next_value()?;
+                    let score = next_value()?;
+                    let phenotype = next_value()?;
+                    hall_of_fame.push(HallOfFameEntry {
+                        phenotype: Pheno::from_checkpoint(phenotype)
+                            .ok_or_else(|| invalid_line(line))?,
+                        score: score.parse::<f64>().map_err(|_| invalid_line(line))?,
+                        generation: generation.parse().map_err(|_| invalid_line(line))?,
+                    });
+                }
+                _ => return Err(invalid_line(line)),
+            }
+        }
+        match (evol_coordinator, evol_options, rng) {
+            (Some(evol_coordinator), Some(evol_options), Some(rng)) if !fitness.is_empty() => {
+                Ok(Self {
+                    evol_coordinator,
+                    evol_options,
+                    rng,
+                    fitness,
+                    hall_of_fame,
+                    novelty_archive,
+                    strategy_state: (!strategy_lines.is_empty()).then(|| strategy_lines.join("\n")),
+                })
+            }
+            _ => Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("incomplete checkpoint {}", path.display()),
+            )),
+        }
+    }
+}
+
+fn invalid_line(line: &str) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid checkpoint line: {}", line),
+    )
+}
@@ -0,0 +1,521 @@
+use std::cell::RefCell;
+
+use super::{
+    checkpoint::Checkpointable,
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    generation_model::GenerationModel,
+    rand::RandomNumberGenerator,
+    selection::Selection,
+    traits::{EvolutionOptionsTrait, EvolutionStrategy, RealVectorPhenotype},
+};
+
+// Covariance Matrix Adaptation Evolution Strategy, samples num_children points around a mean and
+// recombines the num_parents best ones into the next mean, step size and covariance; the weights of the
+// recombination need the parents best first, so only truncation selection is supported, and an update
+// needs a whole generation sampled from the current distribution, which only generational runs breed
+pub struct CmaEsStrategy {
+    initial_sigma: f64,
+    state: RefCell<Option<CmaEsState>>,
+}
+
+impl CmaEsStrategy {
+    pub fn new(initial_sigma: f64) -> Self {
+        Self {
+            initial_sigma,
+            state: RefCell::new(None),
+        }
+    }
+}
+
+impl<Pheno, EvolOptions> EvolutionStrategy<Pheno, EvolOptions> for CmaEsStrategy
+where
+    Pheno: RealVectorPhenotype,
+    EvolOptions: EvolutionOptionsTrait,
+{
+    fn breed(
+        &self,
+        parents: Vec<Pheno>,
+        rng: &mut RandomNumberGenerator,
+        evol_coordinator: EvolutionCoordinator,
+        evol_options: &EvolOptions,
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        if evol_options.get_generation_model() != GenerationModel::Generational {
+            return Err(EvolutionError::InvalidOptions(
+                "CMA-ES needs generational runs".to_string(),
+            ));
+        }
+        if evol_options.get_selection() != Selection::Truncation {
+            return Err(EvolutionError::InvalidOptions(
+                "CMA-ES needs truncation selection".to_string(),
+            ));
+        }
+        let mut state = self.state.borrow_mut();
+        // the first generation of a run samples around the starting value, or around the state learned
+        // from the initial population
+        let current = match state.take() {
+            Some(current) if evol_coordinator.get_current_generation() <= 1 => current,
+            Some(mut current) => {
+                let selected: Vec<Vec<f64>> =
+                    parents.iter().map(|parent| parent.get_values()).collect();
+                current.update(&selected);
+                current
+            }
+            None => CmaEsState::new(parents[0].get_values(), self.initial_sigma),
+        };
+        let children = (0..evol_options.get_num_children())
+            .map(|_| {
+                let mut child = parents[0].clone();
+                child.set_values(&current.sample(rng));
+                child
+            })
+            .collect();
+        *state = Some(current);
+        Ok(children)
+    }
+
+    // the initial population is taken as a sample around its mean, the better half is recombined like
+    // the parents of a generation
+    fn start_run(&self, population: &[Pheno], scores: &[f64]) {
+        if population.is_empty() {
+            self.state.replace(None);
+            return;
+        }
+        let values: Vec<Vec<f64>> = population
+            .iter()
+            .map(|member| member.get_values())
+            .collect();
+        let mean = (0..values[0].len())
+            .map(|i| values.iter().map(|value| value[i]).sum::<f64>() / values.len() as f64)
+            .collect();
+        let mut ranking: Vec<usize> = (0..values.len()).collect();
+        ranking.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        let selected: Vec<Vec<f64>> = ranking
+            .iter()
+            .take(values.len().div_ceil(2))
+            .map(|&i| values[i].clone())
+            .collect();
+        let mut state = CmaEsState::new(mean, self.initial_sigma);
+        state.update(&selected);
+        self.state.replace(Some(state));
+    }
+
+    fn save_state(&self) -> Option<String> {
+        self.state.borrow().as_ref().map(CmaEsState::to_checkpoint)
+    }
+
+    fn load_state(&self, state: Option<&str>) -> Option<()> {
+        self.state
+            .replace(Some(CmaEsState::from_checkpoint(state?)?));
+        Some(())
+    }
+}
+
+struct CmaEsState {
+    mean: Vec<f64>,
+    sigma: f64,
+    covariance: Vec<Vec<f64>>,
+    basis: Vec<Vec<f64>>, // the eigenvectors of the covariance as columns
+    scales: Vec<f64>,     // the square roots of the eigenvalues of the covariance
+    path_sigma: Vec<f64>,
+    path_covariance: Vec<f64>,
+    num_updates: usize,
+}
+
+impl CmaEsState {
+    fn new(mean: Vec<f64>, sigma: f64) -> Self {
+        let dim = mean.len();
+        Self {
+            mean,
+            sigma,
+            covariance: identity(dim),
+            basis: identity(dim),
+            scales: vec![1.0; dim],
+            path_sigma: vec![0.0; dim],
+            path_covariance: vec![0.0; dim],
+            num_updates: 0,
+        }
+    }
+
+    // mean + sigma * B * D * z with z standard normally distributed
+    fn sample(&self, rng: &mut RandomNumberGenerator) -> Vec<f64> {
+        let dim = self.mean.len();
+        let scaled: Vec<f64> = self
+            .scales
+            .iter()
+            .map(|scale| scale * rng.fetch_normal())
+            .collect();
+        (0..dim)
+            .map(|row| {
+                let step: f64 = (0..dim)
+                    .map(|column| self.basis[row][column] * scaled[column])
+                    .sum();
+                self.mean[row] + self.sigma * step
+            })
+            .collect()
+    }
+
+    // the update of Hansen's tutorial, selected holds the best samples of the last generation, best first
+    fn update(&mut self, selected: &[Vec<f64>]) {
+        let dim = self.mean.len();
+        let n = dim as f64;
+        let mu = selected.len();
+        let mut weights: Vec<f64> = (1..=mu)
+            .map(|rank| (mu as f64 + 0.5).ln() - (rank as f64).ln())
+            .collect();
+        let weight_sum: f64 = weights.iter().sum();
+        weights.iter_mut().for_each(|weight| *weight /= weight_sum);
+        let mu_eff = 1.0 / weights.iter().map(|weight| weight * weight).sum::<f64>();
+
+        let c_c = (4.0 + mu_eff / n) / (n + 4.0 + 2.0 * mu_eff / n);
+        let c_sigma = (mu_eff + 2.0) / (n + mu_eff + 5.0);
+        let c_1 = 2.0 / ((n + 1.3) * (n + 1.3) + mu_eff);
+        let c_mu =
+            (1.0 - c_1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((n + 2.0) * (n + 2.0) + mu_eff));
+        let damping = 1.0 + 2.0 * (((mu_eff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+        let expected_norm = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+
+        let steps: Vec<Vec<f64>> = selected
+            .iter()
+            .map(|values| {
+                values
+                    .iter()
+                    .zip(self.mean.iter())
+                    .map(|(value, mean)| (value - mean) / self.sigma)
+                    .collect()
+            })
+            .collect();
+        let mean_step: Vec<f64> = (0..dim)
+            .map(|i| {
+                weights
+                    .iter()
+                    .zip(steps.iter())
+                    .map(|(weight, step)| weight * step[i])
+                    .sum()
+            })
+            .collect();
+        for (mean, step) in self.mean.iter_mut().zip(mean_step.iter()) {
+            *mean += self.sigma * step;
+        }
+
+        let whitened = self.inverse_sqrt_times(&mean_step);
+        let sigma_factor = (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt();
+        for (path, white) in self.path_sigma.iter_mut().zip(whitened.iter()) {
+            *path = (1.0 - c_sigma) * *path + sigma_factor * white;
+        }
+        self.num_updates += 1;
+        let path_sigma_norm = self.path_sigma.iter().map(|x| x * x).sum::<f64>().sqrt();
+        // stalls the covariance path while the step size path is unusually long
+        let stalled = path_sigma_norm
+            / (1.0 - (1.0 - c_sigma).powi(2 * self.num_updates as i32)).sqrt()
+            / expected_norm
+            >= 1.4 + 2.0 / (n + 1.0);
+        let h_sigma = if stalled { 0.0 } else { 1.0 };
+        let covariance_factor = h_sigma * (c_c * (2.0 - c_c) * mu_eff).sqrt();
+        for (path, step) in self.path_covariance.iter_mut().zip(mean_step.iter()) {
+            *path = (1.0 - c_c) * *path + covariance_factor * step;
+        }
+
+        let correction = (1.0 - h_sigma) * c_c * (2.0 - c_c);
+        for row in 0..dim {
+            for column in 0..dim {
+                let rank_one = self.path_covariance[row] * self.path_covariance[column]
+                    + correction * self.covariance[row][column];
+                let rank_mu: f64 = weights
+                    .iter()
+                    .zip(steps.iter())
+                    .map(|(weight, step)| weight * step[row] * step[column])
+                    .sum();
+                self.covariance[row][column] = (1.0 - c_1 - c_mu) * self.covariance[row][column]
+                    + c_1 * rank_one
+                    + c_mu * rank_mu;
+            }
+        }
+        self.sigma *= ((c_sigma / damping) * (path_sigma_norm / expected_norm - 1.0)).exp();
+
+        let (eigenvalues, eigenvectors) = symmetric_eigen(&self.covariance);
+        self.scales = eigenvalues
+            .iter()
+            .map(|eigenvalue| eigenvalue.max(f64::EPSILON).sqrt())
+            .collect();
+        self.basis = eigenvectors;
+    }
+
+    // C^(-1/2) * vector = B * D^-1 * B^T * vector
+    fn inverse_sqrt_times(&self, vector: &[f64]) -> Vec<f64> {
+        let dim = vector.len();
+        let projected: Vec<f64> = (0..dim)
+            .map(|column| {
+                (0..dim)
+                    .map(|row| self.basis[row][column] * vector[row])
+                    .sum::<f64>()
+                    / self.scales[column]
+            })
+            .collect();
+        (0..dim)
+            .map(|row| {
+                (0..dim)
+                    .map(|column| self.basis[row][column] * projected[column])
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+// the dimension and the number of updates followed by sigma and all vectors and matrices row by row
+impl Checkpointable for CmaEsState {
+    fn to_checkpoint(&self) -> String {
+        let values = [self.sigma]
+            .iter()
+            .chain(self.mean.iter())
+            .chain(self.covariance.iter().flatten())
+            .chain(self.basis.iter().flatten())
+            .chain(self.scales.iter())
+            .chain(self.path_sigma.iter())
+            .chain(self.path_covariance.iter())
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("{} {} {}", self.mean.len(), self.num_updates, values)
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut tokens = line.split(' ');
+        let dim: usize = tokens.next()?.parse().ok()?;
+        let num_updates = tokens.next()?.parse().ok()?;
+        let values = tokens
+            .map(|token| token.parse().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        if values.len() != 1 + 4 * dim + 2 * dim * dim {
+            return None;
+        }
+        let mut values = values.into_iter();
+        let mut take = |len: usize| values.by_ref().take(len).collect::<Vec<f64>>();
+        let sigma = take(1)[0];
+        let mean = take(dim);
+        let mut take_matrix = || (0..dim).map(|_| take(dim)).collect::<Vec<Vec<f64>>>();
+        let covariance = take_matrix();
+        let basis = take_matrix();
+        Some(Self {
+            sigma,
+            mean,
+            covariance,
+            basis,
+            scales: take(dim),
+            path_sigma: take(dim),
+            path_covariance: take(dim),
+            num_updates,
+        })
+    }
+}
+
+fn identity(dim: usize) -> Vec<Vec<f64>> {
+    (0..dim)
+        .map(|row| {
+            (0..dim)
+                .map(|column| if row == column { 1.0 } else { 0.0 })
+                .collect()
+        })
+        .collect()
+}
+
+// cyclic Jacobi eigenvalue algorithm, returns the eigenvalues and the eigenvectors as columns
+fn symmetric_eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let dim = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut v = identity(dim);
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..dim)
+            .flat_map(|row| {
+                (0..dim)
+                    .filter(move |&column| column != row)
+                    .map(move |column| (row, column))
+            })
+            .map(|(row, column)| a[row][column] * a[row][column])
+            .sum();
+        if off_diagonal < 1e-30 {
+            break;
+        }
+        for p in 0..dim {
+            for q in p + 1..dim {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (apk, aqk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (old_apk, old_aqk) = (*apk, *aqk);
+                    *apk = c * old_apk - s * old_aqk;
+                    *aqk = s * old_apk + c * old_aqk;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..dim).map(|i| a[i][i]).collect(), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        error::EvolutionError,
+        evol_launcher::EvolutionLauncher,
+        evol_options::EvolutionOptions,
+        generation_model::{GenerationModel, Replacement},
+        observer::{EvolutionObserver, GenerationReport, ObserverAction},
+        rand::RandomNumberGenerator,
+        selection::Selection,
+        termination::StopReason,
+        test_evol::XCoordinates,
+    };
+
+    use super::{symmetric_eigen, CmaEsStrategy};
+
+    fn rosenbrock(xs: &[f64]) -> f64 {
+        xs.windows(2)
+            .map(|pair| 100.0 * (pair[1] - pair[0] * pair[0]).powi(2) + (1.0 - pair[0]).powi(2))
+            .sum()
+    }
+
+    // an ill-conditioned quadratic, the axis scales span six orders of magnitude
+    fn ellipsoid(xs: &[f64]) -> f64 {
+        let n = xs.len() as f64;
+        xs.iter()
+            .enumerate()
+            .map(|(i, x)| 10f64.powf(6.0 * i as f64 / (n - 1.0)) * x * x)
+            .sum()
+    }
+
+    struct StopObserver {
+        generation: usize,
+    }
+
+    impl EvolutionObserver<XCoordinates> for StopObserver {
+        fn on_generation(&mut self, report: &GenerationReport<XCoordinates>) -> ObserverAction {
+            if report.generation == self.generation {
+                return ObserverAction::Stop;
+            }
+            ObserverAction::Continue
+        }
+    }
+
+    fn new_launcher() -> EvolutionLauncher<XCoordinates, EvolutionOptions, CmaEsStrategy> {
+        EvolutionLauncher::new(
+            CmaEsStrategy::new(0.5),
+            Box::new(|phenotype: &XCoordinates| -rosenbrock(phenotype.xs())),
+        )
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let matrix = vec![vec![2.0, 1.0], vec![1.0, 2.0]];
+        let (eigenvalues, eigenvectors) = symmetric_eigen(&matrix);
+        for (i, eigenvalue) in eigenvalues.iter().enumerate() {
+            for row in 0..2 {
+                let product: f64 = (0..2)
+                    .map(|column| matrix[row][column] * eigenvectors[column][i])
+                    .sum();
+                assert!((product - eigenvalue * eigenvectors[row][i]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let launcher: EvolutionLauncher<XCoordinates, EvolutionOptions, CmaEsStrategy> =
+            EvolutionLauncher::new(
+                CmaEsStrategy::new(0.5),
+                Box::new(|phenotype: &XCoordinates| -rosenbrock(phenotype.xs())),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new().with_num_parents(10);
+        let winner = launcher
+            .evolve(evol_options, XCoordinates::new(vec![-1.0, 1.0]), &mut rng)
+            .unwrap()
+            .winner;
+        for x in winner.xs() {
+            assert!((x - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_ellipsoid() {
+        let launcher: EvolutionLauncher<XCoordinates, EvolutionOptions, CmaEsStrategy> =
+            EvolutionLauncher::new(
+                CmaEsStrategy::new(1.0),
+                Box::new(|phenotype: &XCoordinates| -ellipsoid(phenotype.xs())),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new()
+            .with_num_generations(200)
+            .with_num_parents(10);
+        let result = launcher
+            .evolve(evol_options, XCoordinates::new(vec![1.0; 5]), &mut rng)
+            .unwrap();
+        assert!(-result.score < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let steady_state = GenerationModel::SteadyState {
+            num_offspring: 1,
+            replacement: Replacement::Worst,
+        };
+        for evol_options in [
+            EvolutionOptions::new().with_selection(Selection::RouletteWheel),
+            EvolutionOptions::new().with_generation_model(steady_state),
+        ] {
+            let result =
+                new_launcher().evolve(evol_options, XCoordinates::new(vec![0.0; 2]), &mut rng);
+            assert!(matches!(result, Err(EvolutionError::InvalidOptions(_))));
+        }
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint_path =
+            std::env::temp_dir().join(format!("cma_es_checkpoint_{}.txt", std::process::id()));
+        let evol_options = EvolutionOptions::new()
+            .with_num_generations(40)
+            .with_num_parents(10);
+        let starting_value = XCoordinates::new(vec![-1.0, 1.0]);
+        let expected = new_launcher()
+            .evolve(
+                evol_options,
+                starting_value.clone(),
+                &mut RandomNumberGenerator::from_seed(0),
+            )
+            .unwrap();
+        // the run is cancelled after the checkpoint of generation 20 and resumed by a new strategy
+        let mut launcher = new_launcher();
+        launcher.add_observer(Box::new(StopObserver { generation: 20 }));
+        let cancelled = launcher
+            .evolve_with_checkpoints(
+                evol_options,
+                starting_value,
+                &mut RandomNumberGenerator::from_seed(0),
+                &checkpoint_path,
+                10,
+            )
+            .unwrap();
+        assert_eq!(cancelled.stop_reason, StopReason::Cancelled);
+        let resumed = new_launcher().resume(&checkpoint_path, 10).unwrap();
+        std::fs::remove_file(&checkpoint_path).unwrap();
+        assert_eq!(resumed.stop_reason, StopReason::MaxGenerations);
+        assert_eq!(resumed.winner.xs(), expected.winner.xs());
+        assert_eq!(resumed.score, expected.score);
+    }
+}
@@ -0,0 +1,490 @@
+use std::cell::RefCell;
+
+use super::{
+    checkpoint::Checkpointable,
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    generation_model::GenerationModel,
+    rand::RandomNumberGenerator,
+    traits::{EvolutionOptionsTrait, EvolutionStrategy, RealVectorPhenotype},
+};
+
+// the smallest population that has three distinct partners for every target
+const MIN_POPULATION: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DifferentialVariant {
+    RandOneBin,       // v = x_r1 + F * (x_r2 - x_r3), binomial crossover with the target
+    BestOneBin,       // v = x_best + F * (x_r1 - x_r2), binomial crossover with the target
+    CurrentToBestOne, // v = x_i + F * (x_best - x_i) + F * (x_r1 - x_r2), without crossover
+}
+
+// keeps a population of num_children members, breeds one trial per member and lets every trial replace
+// its target if it scores at least as well; the first candidates are the population itself unless it
+// was taken over with its scores from the initial population of the run, after that
+// the trials followed by the best member, so the targets keep their scores and the run's winner is never
+// lost; it needs the scores of every generation and its own replacement, so it does not support steady
+// state generations and multi-objective launchers
+pub struct DifferentialEvolutionStrategy {
+    variant: DifferentialVariant,
+    differential_weight: f64, // F
+    crossover_rate: f64,      // CR
+    state: RefCell<Option<DifferentialState>>,
+}
+
+struct DifferentialState {
+    population: Vec<Vec<f64>>,
+    scores: Vec<f64>, // empty until the population has been scored
+}
+
+// the number of members and of scores followed by the members one after another and the scores
+impl Checkpointable for DifferentialState {
+    fn to_checkpoint(&self) -> String {
+        let values = self
+            .population
+            .iter()
+            .flatten()
+            .chain(self.scores.iter())
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("{} {} {}", self.population.len(), self.scores.len(), values)
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut tokens = line.split(' ');
+        let population_size: usize = tokens.next()?.parse().ok()?;
+        let num_scores: usize = tokens.next()?.parse().ok()?;
+        let mut values = tokens
+            .map(|token| token.parse().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        let num_values = values.len().checked_sub(num_scores)?;
+        if population_size < MIN_POPULATION
+            || num_values == 0
+            || num_values % population_size != 0
+            || (num_scores != 0 && num_scores != population_size)
+        {
+            return None;
+        }
+        let scores = values.split_off(num_values);
+        Some(Self {
+            population: values
+                .chunks(num_values / population_size)
+                .map(<[f64]>::to_vec)
+                .collect(),
+            scores,
+        })
+    }
+}
+
+impl DifferentialEvolutionStrategy {
+    pub fn new(
+        variant: DifferentialVariant,
+        differential_weight: f64,
+        crossover_rate: f64,
+    ) -> Self {
+        Self {
+            variant,
+            differential_weight,
+            crossover_rate,
+            state: RefCell::new(None),
+        }
+    }
+
+    fn trial(
+        &self,
+        population: &[Vec<f64>],
+        best: usize,
+        target: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<f64> {
+        let f = self.differential_weight;
+        let x = &population[target];
+        let best = &population[best];
+        let partners = pick_distinct(population.len(), target, rng);
+        let (r1, r2, r3) = (
+            &population[partners[0]],
+            &population[partners[1]],
+            &population[partners[2]],
+        );
+        match self.variant {
+            DifferentialVariant::RandOneBin => {
+                let mutant: Vec<f64> = (0..x.len()).map(|j| r1[j] + f * (r2[j] - r3[j])).collect();
+                self.binomial_crossover(x, &mutant, rng)
+            }
+            DifferentialVariant::BestOneBin => {
+                let mutant: Vec<f64> = (0..x.len())
+                    .map(|j| best[j] + f * (r1[j] - r2[j]))
+                    .collect();
+                self.binomial_crossover(x, &mutant, rng)
+            }
+            DifferentialVariant::CurrentToBestOne => (0..x.len())
+                .map(|j| x[j] + f * (best[j] - x[j]) + f * (r1[j] - r2[j]))
+                .collect(),
+        }
+    }
+
+    // takes each value from the mutant with probability CR and at least one of them
+    fn binomial_crossover(
+        &self,
+        target: &[f64],
+        mutant: &[f64],
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<f64> {
+        let forced = rng.fetch_index(target.len());
+        target
+            .iter()
+            .zip(mutant.iter())
+            .enumerate()
+            .map(|(j, (&target_value, &mutant_value))| {
+                if j == forced || rng.fetch_unit() < self.crossover_rate {
+                    mutant_value
+                } else {
+                    target_value
+                }
+            })
+            .collect()
+    }
+}
+
+impl<Pheno, EvolOptions> EvolutionStrategy<Pheno, EvolOptions> for DifferentialEvolutionStrategy
+where
+    Pheno: RealVectorPhenotype,
+    EvolOptions: EvolutionOptionsTrait,
+{
+    fn breed(
+        &self,
+        parents: Vec<Pheno>,
+        rng: &mut RandomNumberGenerator,
+        evol_coordinator: EvolutionCoordinator,
+        evol_options: &EvolOptions,
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        if evol_options.get_generation_model() != GenerationModel::Generational {
+            return Err(EvolutionError::InvalidOptions(
+                "differential evolution needs generational runs".to_string(),
+            ));
+        }
+        let population_size = evol_options.get_num_children().max(MIN_POPULATION);
+        let to_phenotype = |values: &Vec<f64>| {
+            let mut child = parents[0].clone();
+            child.set_values(values);
+            child
+        };
+        // the parents, usually the starting value alone, start the population unless the run started
+        // from an initial population
+        let mut state = self.state.borrow_mut();
+        let state = state.get_or_insert_with(|| DifferentialState {
+            population: parents.iter().map(|parent| parent.get_values()).collect(),
+            scores: Vec::new(),
+        });
+        // the best members are kept, the missing ones are spread out by mutation; the difference vectors
+        // hardly reach beyond this spread, so mutate should cover the region of interest
+        state.population.truncate(population_size);
+        state.scores.truncate(population_size);
+        while state.population.len() < population_size {
+            let mut member =
+                to_phenotype(&state.population[rng.fetch_index(state.population.len())]);
+            member.mutate(rng, evol_coordinator);
+            state.population.push(member.get_values());
+            state.scores.clear();
+        }
+        if state.scores.is_empty() {
+            return Ok(state.population.iter().map(to_phenotype).collect());
+        }
+        let best = (0..state.scores.len())
+            .max_by(|&a, &b| state.scores[a].total_cmp(&state.scores[b]))
+            .expect("the population is not empty");
+        let trials: Vec<Vec<f64>> = (0..state.population.len())
+            .map(|target| self.trial(&state.population, best, target, rng))
+            .collect();
+        Ok(trials
+            .iter()
+            .chain([&state.population[best]])
+            .map(to_phenotype)
+            .collect())
+    }
+
+    // the initial population is sorted best first, so that the best members are kept if it is larger
+    // than the population
+    fn start_run(&self, population: &[Pheno], scores: &[f64]) {
+        if population.is_empty() {
+            self.state.replace(None);
+            return;
+        }
+        let mut ranking: Vec<usize> = (0..population.len()).collect();
+        ranking.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        self.state.replace(Some(DifferentialState {
+            population: ranking
+                .iter()
+                .map(|&i| population[i].get_values())
+                .collect(),
+            scores: ranking.iter().map(|&i| scores[i]).collect(),
+        }));
+    }
+
+    fn record_scores(&self, candidates: &[Pheno], scores: &[f64]) -> Result<(), EvolutionError> {
+        let mut state = self.state.borrow_mut();
+        let Some(state) = state.as_mut() else {
+            return Ok(());
+        };
+        let population_size = state.population.len();
+        // the population itself is scored once, the best member after the trials keeps its score
+        let expected = if state.scores.is_empty() {
+            population_size
+        } else {
+            population_size + 1
+        };
+        if scores.len() != expected || candidates.len() != expected {
+            return Err(EvolutionError::ScoreCountMismatch {
+                expected,
+                actual: scores.len().min(candidates.len()),
+            });
+        }
+        if state.scores.is_empty() {
+            state.scores = scores.to_vec();
+            return Ok(());
+        }
+        let trials = candidates.iter().zip(scores).take(population_size);
+        for (i, (trial, &trial_score)) in trials.enumerate() {
+            if trial_score >= state.scores[i] {
+                state.population[i] = trial.get_values();
+                state.scores[i] = trial_score;
+            }
+        }
+        Ok(())
+    }
+
+    fn needs_scores(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<String> {
+        self.state
+            .borrow()
+            .as_ref()
+            .map(DifferentialState::to_checkpoint)
+    }
+
+    fn load_state(&self, state: Option<&str>) -> Option<()> {
+        self.state
+            .replace(Some(DifferentialState::from_checkpoint(state?)?));
+        Some(())
+    }
+}
+
+// three distinct indices in 0..len that differ from excluded
+fn pick_distinct(len: usize, excluded: usize, rng: &mut RandomNumberGenerator) -> Vec<usize> {
+    let mut picked = Vec::with_capacity(3);
+    while picked.len() < 3 {
+        let index = rng.fetch_index(len);
+        if index != excluded && !picked.contains(&index) {
+            picked.push(index);
+        }
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::evol::{
+        error::EvolutionError,
+        evol_coordinator::EvolutionCoordinator,
+        evol_launcher::EvolutionLauncher,
+        evol_options::EvolutionOptions,
+        generation_model::{GenerationModel, Replacement},
+        multi_objective_launcher::MultiObjectiveLauncher,
+        observer::{EvolutionObserver, GenerationReport, ObserverAction},
+        rand::RandomNumberGenerator,
+        termination::StopReason,
+        test_evol::{XCoordinates, XCoordinatesChallenge},
+        traits::EvolutionStrategy,
+    };
+
+    use super::{DifferentialEvolutionStrategy, DifferentialVariant};
+
+    struct StopObserver {
+        generation: usize,
+    }
+
+    impl EvolutionObserver<XCoordinates> for StopObserver {
+        fn on_generation(&mut self, report: &GenerationReport<XCoordinates>) -> ObserverAction {
+            if report.generation == self.generation {
+                return ObserverAction::Stop;
+            }
+            ObserverAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_variants() {
+        let variants = [
+            DifferentialVariant::RandOneBin,
+            DifferentialVariant::BestOneBin,
+            DifferentialVariant::CurrentToBestOne,
+        ];
+        for variant in variants {
+            let challenge = XCoordinatesChallenge::new(vec![0.5, -0.5, 0.25]);
+            let launcher: EvolutionLauncher<
+                XCoordinates,
+                EvolutionOptions,
+                DifferentialEvolutionStrategy,
+            > = EvolutionLauncher::new(
+                DifferentialEvolutionStrategy::new(variant, 0.5, 0.9),
+                Box::new(move |phenotype: &XCoordinates| challenge.score(phenotype)),
+            );
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            let evol_options = EvolutionOptions::new()
+                .with_num_generations(200)
+                .with_num_parents(20)
+                .with_num_children(40);
+            let winner = launcher
+                .evolve(evol_options, XCoordinates::new(vec![0.0; 3]), &mut rng)
+                .unwrap()
+                .winner;
+            for (x, target) in winner.xs().iter().zip([0.5, -0.5, 0.25]) {
+                assert!((x - target).abs() < 1e-3, "{:?}", variant);
+            }
+        }
+    }
+
+    #[test]
+    fn test_targets_keep_their_scores() {
+        let num_evaluations = Arc::new(AtomicUsize::new(0));
+        let counter = num_evaluations.clone();
+        let challenge = XCoordinatesChallenge::new(vec![0.5, -0.5]);
+        let launcher: EvolutionLauncher<
+            XCoordinates,
+            EvolutionOptions,
+            DifferentialEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            DifferentialEvolutionStrategy::new(DifferentialVariant::RandOneBin, 0.5, 0.9),
+            Box::new(move |phenotype: &XCoordinates| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                challenge.score(phenotype)
+            }),
+        );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new()
+            .with_num_generations(10)
+            .with_num_children(8);
+        launcher
+            .evolve(evol_options, XCoordinates::new(vec![0.0; 2]), &mut rng)
+            .unwrap();
+        // the population once, then the trials and the best member every generation
+        assert_eq!(num_evaluations.load(Ordering::Relaxed), 8 + 9 * (8 + 1));
+    }
+
+    #[test]
+    fn test_errors() {
+        let new_strategy =
+            || DifferentialEvolutionStrategy::new(DifferentialVariant::RandOneBin, 0.5, 0.9);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let steady_state =
+            EvolutionOptions::new().with_generation_model(GenerationModel::SteadyState {
+                num_offspring: 1,
+                replacement: Replacement::Worst,
+            });
+        let launcher: EvolutionLauncher<
+            XCoordinates,
+            EvolutionOptions,
+            DifferentialEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            new_strategy(),
+            Box::new(|phenotype: &XCoordinates| -phenotype.xs()[0].abs()),
+        );
+        let result = launcher.evolve(steady_state, XCoordinates::new(vec![0.0; 2]), &mut rng);
+        assert!(matches!(result, Err(EvolutionError::InvalidOptions(_))));
+
+        let launcher: MultiObjectiveLauncher<
+            XCoordinates,
+            EvolutionOptions,
+            DifferentialEvolutionStrategy,
+        > = MultiObjectiveLauncher::new(
+            new_strategy(),
+            Box::new(|phenotype: &XCoordinates| phenotype.xs().clone()),
+        );
+        let result = launcher.evolve(
+            EvolutionOptions::new(),
+            XCoordinates::new(vec![0.0; 2]),
+            &mut rng,
+        );
+        assert!(matches!(result, Err(EvolutionError::InvalidOptions(_))));
+
+        let strategy = new_strategy();
+        let evol_options = EvolutionOptions::new().with_num_children(4);
+        let evol_coordinator = EvolutionCoordinator::new(&evol_options);
+        let candidates = strategy
+            .breed(
+                vec![XCoordinates::new(vec![0.0; 2])],
+                &mut rng,
+                evol_coordinator,
+                &evol_options,
+            )
+            .unwrap();
+        let result = EvolutionStrategy::<XCoordinates, EvolutionOptions>::record_scores(
+            &strategy,
+            &candidates[..3],
+            &[0.0; 3],
+        );
+        assert!(matches!(
+            result,
+            Err(EvolutionError::ScoreCountMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint_path = std::env::temp_dir().join(format!(
+            "differential_checkpoint_{}.txt",
+            std::process::id()
+        ));
+        let new_launcher = || -> EvolutionLauncher<
+            XCoordinates,
+            EvolutionOptions,
+            DifferentialEvolutionStrategy,
+        > {
+            let challenge = XCoordinatesChallenge::new(vec![0.5, -0.5]);
+            EvolutionLauncher::new(
+                DifferentialEvolutionStrategy::new(DifferentialVariant::RandOneBin, 0.5, 0.9),
+                Box::new(move |phenotype: &XCoordinates| challenge.score(phenotype)),
+            )
+        };
+        let evol_options = EvolutionOptions::new()
+            .with_num_generations(40)
+            .with_num_children(10);
+        let starting_value = XCoordinates::new(vec![0.0; 2]);
+        let expected = new_launcher()
+            .evolve(
+                evol_options,
+                starting_value.clone(),
+                &mut RandomNumberGenerator::from_seed(0),
+            )
+            .unwrap();
+        // the run is cancelled after the checkpoint of generation 20 and resumed by a new strategy
+        let mut launcher = new_launcher();
+        launcher.add_observer(Box::new(StopObserver { generation: 20 }));
+        let cancelled = launcher
+            .evolve_with_checkpoints(
+                evol_options,
+                starting_value,
+                &mut RandomNumberGenerator::from_seed(0),
+                &checkpoint_path,
+                10,
+            )
+            .unwrap();
+        assert_eq!(cancelled.stop_reason, StopReason::Cancelled);
+        let resumed = new_launcher().resume(&checkpoint_path, 10).unwrap();
+        std::fs::remove_file(&checkpoint_path).unwrap();
+        assert_eq!(resumed.stop_reason, StopReason::MaxGenerations);
+        assert_eq!(resumed.winner.xs(), expected.winner.xs());
+        assert_eq!(resumed.score, expected.score);
+    }
+}
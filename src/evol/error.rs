@@ -0,0 +1,88 @@
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum EvolutionError {
+    // no phenotype within the magnitude range was found within the retry budget
+    InfeasibleRegion {
+        min_magnitude: f64,
+        max_magnitude: f64,
+        num_attempts: usize,
+    },
+    // the score function returned NaN or an infinity and the NonFiniteScorePolicy rejects it,
+    // the phenotype is given by its to_string_internal
+    NonFiniteScore {
+        phenotype: String,
+        score: f64,
+    },
+    // there were no parents to breed from or the strategy bred no candidates
+    EmptyPopulation,
+    // a checkpoint could not be written or read
+    Checkpoint(io::Error),
+    // the options ask for something the launcher or the strategy does not support
+    InvalidOptions(String),
+    // a strategy received a different number of scores than it bred candidates
+    ScoreCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    // a multi-objective score function returned another number of objectives than for the starting value
+    ObjectiveCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    // a feature function returned another number of features than the map elites archive has dimensions
+    FeatureCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for EvolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvolutionError::InfeasibleRegion {
+                min_magnitude,
+                max_magnitude,
+                num_attempts,
+            } => write!(
+                f,
+                "no phenotype with a magnitude between {} and {} found in {} attempts",
+                min_magnitude, max_magnitude, num_attempts
+            ),
+            EvolutionError::NonFiniteScore { phenotype, score } => {
+                write!(f, "the score of phenotype {} is {}", phenotype, score)
+            }
+            EvolutionError::EmptyPopulation => write!(f, "the population is empty"),
+            EvolutionError::Checkpoint(error) => write!(f, "checkpoint failed: {}", error),
+            EvolutionError::InvalidOptions(reason) => write!(f, "invalid options: {}", reason),
+            EvolutionError::ScoreCountMismatch { expected, actual } => {
+                write!(f, "expected {} scores but received {}", expected, actual)
+            }
+            EvolutionError::ObjectiveCountMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "expected {} objectives but received {}",
+                    expected, actual
+                )
+            }
+            EvolutionError::FeatureCountMismatch { expected, actual } => {
+                write!(f, "expected {} features but received {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl Error for EvolutionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EvolutionError::Checkpoint(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EvolutionError {
+    fn from(error: io::Error) -> Self {
+        EvolutionError::Checkpoint(error)
+    }
+}
@@ -1,9 +1,28 @@
-use super::traits::EvolutionOptionsTrait;
+use std::time::{Duration, Instant};
+
+use super::{
+    checkpoint::Checkpointable,
+    termination::{StopReason, TerminationCriteria},
+    traits::EvolutionOptionsTrait,
+};
+
+// bounds the success exponent, so that a long run of successes or failures can not push the step size of
+// the one fifth rule to 0 or infinity
+const MAX_SUCCESS_EXPONENT: i32 = 32;
 
 #[derive(Clone, Copy)]
 pub struct EvolutionCoordinator {
     current_generation: usize,
     num_generations: usize,
+    best_score: f64, // the best score of all generations so far
+    generations_without_improvement: usize, // the generations since best_score improved
+    num_evaluations: usize, // the calls to the score function so far
+    score_spread: f64, // the standard deviation of the last scores
+    diversity: f64, // the mean distance between the last phenotypes, infinite unless the launcher measures it
+    success_rate: f64, // the share of the last scores that beat the previous best score
+    success_exponent: i32, // counts generations above minus generations below a success rate of 1/5
+    cancelled: bool,
+    start: Instant,
 }
 
 impl EvolutionCoordinator {
@@ -14,15 +33,142 @@ impl EvolutionCoordinator {
         EvolutionCoordinator {
             current_generation: 0,
             num_generations: options.get_num_generations(),
+            best_score: f64::NEG_INFINITY,
+            generations_without_improvement: 0,
+            num_evaluations: 0,
+            score_spread: f64::INFINITY,
+            diversity: f64::INFINITY,
+            success_rate: 0.0,
+            success_exponent: 0,
+            cancelled: false,
+            start: Instant::now(),
         }
     }
 
+    // advances the coordinator by one generation
     pub fn run(&mut self) {
-        while self.current_generation < self.num_generations {
+        if self.current_generation < self.num_generations {
             self.current_generation += 1;
         }
     }
 
+    // keeps track of the scores of the current generation for the termination criteria
+    pub fn record_scores(&mut self, scores: &[f64], num_evaluations: usize) {
+        self.record_population(scores, scores.len(), num_evaluations);
+    }
+
+    // like record_scores for a population of which only num_children members were bred in the current
+    // generation, the spread and the stagnation go by the whole population and the success rate by the
+    // children that beat the best score so far
+    pub fn record_population(
+        &mut self,
+        scores: &[f64],
+        num_children: usize,
+        num_evaluations: usize,
+    ) {
+        self.num_evaluations += num_evaluations;
+        // the first generation has nothing to beat
+        if self.best_score > f64::NEG_INFINITY && !scores.is_empty() {
+            let successes = scores
+                .iter()
+                .filter(|&&score| score > self.best_score)
+                .count();
+            self.record_successes(successes, num_children);
+        }
+        let best_score = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if best_score > self.best_score {
+            self.best_score = best_score;
+            self.generations_without_improvement = 0;
+        } else {
+            self.generations_without_improvement += 1;
+        }
+        if !scores.is_empty() {
+            let mean = scores.iter().sum::<f64>() / scores.len() as f64;
+            let variance = scores
+                .iter()
+                .map(|score| (score - mean) * (score - mean))
+                .sum::<f64>()
+                / scores.len() as f64;
+            self.score_spread = variance.sqrt();
+        }
+    }
+
+    pub fn record_diversity(&mut self, diversity: f64) {
+        self.diversity = diversity;
+    }
+
+    // counts the calls to the score function of launchers without a single score per phenotype
+    pub fn record_evaluations(&mut self, num_evaluations: usize) {
+        self.num_evaluations += num_evaluations;
+    }
+
+    // keeps track of the share of successful children for the one fifth rule, for launchers whose
+    // children have no single score to compare with the best score
+    pub fn record_successes(&mut self, successes: usize, num_children: usize) {
+        if num_children == 0 {
+            return;
+        }
+        self.success_rate = successes as f64 / num_children as f64;
+        if self.success_rate > 0.2 {
+            self.success_exponent += 1;
+        } else if self.success_rate < 0.2 {
+            self.success_exponent -= 1;
+        }
+        self.success_exponent = self
+            .success_exponent
+            .clamp(-MAX_SUCCESS_EXPONENT, MAX_SUCCESS_EXPONENT);
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    pub fn get_stop_reason(&self, criteria: &TerminationCriteria) -> Option<StopReason> {
+        if self.cancelled {
+            return Some(StopReason::Cancelled);
+        }
+        if self.current_generation >= self.num_generations {
+            return Some(StopReason::MaxGenerations);
+        }
+        if criteria
+            .get_target_score()
+            .is_some_and(|target_score| self.best_score >= target_score)
+        {
+            return Some(StopReason::TargetScoreReached);
+        }
+        if criteria
+            .get_max_stagnation()
+            .is_some_and(|max_stagnation| self.generations_without_improvement >= max_stagnation)
+        {
+            return Some(StopReason::Stagnation);
+        }
+        if criteria
+            .get_min_score_spread()
+            .is_some_and(|min_score_spread| self.score_spread < min_score_spread)
+        {
+            return Some(StopReason::LowScoreSpread);
+        }
+        if criteria
+            .get_min_diversity()
+            .is_some_and(|min_diversity| self.diversity < min_diversity)
+        {
+            return Some(StopReason::LowDiversity);
+        }
+        if criteria
+            .get_time_budget()
+            .is_some_and(|time_budget| self.start.elapsed() >= time_budget)
+        {
+            return Some(StopReason::TimeBudgetExhausted);
+        }
+        if criteria
+            .get_max_evaluations()
+            .is_some_and(|max_evaluations| self.num_evaluations >= max_evaluations)
+        {
+            return Some(StopReason::MaxEvaluationsReached);
+        }
+        None
+    }
+
     pub fn get_progress(&self) -> f64 {
         self.current_generation as f64 / self.num_generations as f64
     }
@@ -30,4 +176,58 @@ impl EvolutionCoordinator {
     pub fn get_current_generation(&self) -> usize {
         self.current_generation
     }
+
+    pub fn get_best_score(&self) -> f64 {
+        self.best_score
+    }
+
+    pub fn get_num_evaluations(&self) -> usize {
+        self.num_evaluations
+    }
+
+    pub fn get_success_rate(&self) -> f64 {
+        self.success_rate
+    }
+
+    pub fn get_success_exponent(&self) -> i32 {
+        self.success_exponent
+    }
+}
+
+// the elapsed time is stored so that a resumed run keeps its time budget, a cancel only ends the run it
+// was requested in and is not stored, and the diversity is measured again from the stored population
+impl Checkpointable for EvolutionCoordinator {
+    fn to_checkpoint(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {} {} {}",
+            self.current_generation,
+            self.num_generations,
+            self.best_score,
+            self.generations_without_improvement,
+            self.num_evaluations,
+            self.score_spread,
+            self.success_rate,
+            self.success_exponent,
+            self.start.elapsed().as_nanos()
+        )
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut values = line.split(' ');
+        Some(EvolutionCoordinator {
+            current_generation: values.next()?.parse().ok()?,
+            num_generations: values.next()?.parse().ok()?,
+            best_score: values.next()?.parse().ok()?,
+            generations_without_improvement: values.next()?.parse().ok()?,
+            num_evaluations: values.next()?.parse().ok()?,
+            score_spread: values.next()?.parse().ok()?,
+            diversity: f64::INFINITY,
+            success_rate: values.next()?.parse().ok()?,
+            success_exponent: values.next()?.parse().ok()?,
+            cancelled: false,
+            start: Instant::now()
+                .checked_sub(Duration::from_nanos(values.next()?.parse().ok()?))
+                .unwrap_or_else(Instant::now),
+        })
+    }
 }
@@ -1,25 +1,67 @@
-use std::marker::PhantomData;
+use std::{cell::RefCell, collections::HashMap, io, marker::PhantomData, path::Path};
 
 use super::{
+    checkpoint::{Checkpoint, Checkpointable},
+    error::EvolutionError,
     evol_coordinator::EvolutionCoordinator,
+    evol_options::NoveltyOptions,
+    fitness_cache::{CacheStats, FitnessCache},
+    generation_model::GenerationModel,
+    hall_of_fame::{HallOfFame, HallOfFameEntry},
+    initialisation::Initialisation,
+    novelty::{BehaviourFn, NoveltySearch},
+    observer::{EvolutionObserver, GenerationReport, LogObserver, ObserverAction},
     rand::RandomNumberGenerator,
-    traits::{EvolutionOptionsTrait, EvolutionStrategy, Phenotype},
+    score_policy::NonFiniteScorePolicy,
+    scorer::{ParallelScorer, ScoreFn, Scorer, SerialScorer, SyncScoreFn},
+    selection::SelectionOperator,
+    termination::StopReason,
+    traits::{
+        EvolutionOptionsTrait, EvolutionStrategy, Fingerprint, InitialisablePhenotype, Phenotype,
+        PhenotypeDistance,
+    },
 };
 
+#[derive(Clone)]
+pub struct Fitness<Pheno: Phenotype> {
+    pub phenotype: Pheno,
+    pub score: f64, // the objective the winner, the hall of fame and the termination go by
+    pub ranking_score: f64, // the score the parents are selected by, blended with the novelty if searched
+}
+
+impl<Pheno: Phenotype> Fitness<Pheno> {
+    // without a novelty search the parents are selected by the score itself
+    pub fn new(phenotype: Pheno, score: f64) -> Self {
+        Self {
+            phenotype,
+            score,
+            ranking_score: score,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct EvolutionResult<Pheno: Phenotype> {
     pub winner: Pheno,
     pub score: f64,
+    pub stop_reason: StopReason,
+    pub hall_of_fame: Vec<HallOfFameEntry<Pheno>>, // the best distinct phenotypes of the whole run, best first
 }
 
-pub struct EvolutionLauncher<Pheno, EvolOptions, Strategy>
+// scores on the calling thread unless it is created with new_parallel
+pub struct EvolutionLauncher<Pheno, EvolOptions, Strategy, Scoring = SerialScorer<Pheno, f64>>
 where
     Pheno: Phenotype,
     EvolOptions: EvolutionOptionsTrait,
     Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
 {
     strategy: Strategy,
-    score_fn: Box<dyn Fn(Pheno) -> f64>,
+    scorer: Scoring,
+    observers: RefCell<Vec<Box<dyn EvolutionObserver<Pheno> + Send>>>,
+    fitness_cache: RefCell<Option<FitnessCache<Pheno>>>, // kept across runs, the score function does not change
+    novelty_search: RefCell<Option<NoveltySearch<Pheno>>>,
+    diversity_fn: Option<DiversityFn<Pheno>>,
     _marker: PhantomData<(Pheno, EvolOptions)>,
 }
 
@@ -29,84 +71,678 @@ where
     EvolOptions: EvolutionOptionsTrait,
     Strategy: EvolutionStrategy<Pheno, EvolOptions>,
 {
-    pub fn new(strategy: Strategy, score_fn: Box<dyn Fn(Pheno) -> f64>) -> Self {
+    pub fn new(strategy: Strategy, score_fn: Box<ScoreFn<Pheno, f64>>) -> Self {
+        Self::with_scorer(strategy, SerialScorer::new(score_fn))
+    }
+}
+
+impl<Pheno, EvolOptions, Strategy>
+    EvolutionLauncher<Pheno, EvolOptions, Strategy, ParallelScorer<Pheno, f64>>
+where
+    Pheno: Phenotype + Send + 'static,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+{
+    // scores the candidates on the num_threads of the options, the threads are kept between generations
+    // and runs of the launcher
+    pub fn new_parallel(strategy: Strategy, score_fn: Box<SyncScoreFn<Pheno, f64>>) -> Self {
+        Self::with_scorer(strategy, ParallelScorer::new(score_fn))
+    }
+}
+
+impl<Pheno, EvolOptions, Strategy, Scoring> EvolutionLauncher<Pheno, EvolOptions, Strategy, Scoring>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
+{
+    fn with_scorer(strategy: Strategy, scorer: Scoring) -> Self {
         Self {
             strategy,
-            score_fn,
+            scorer,
+            observers: RefCell::new(Vec::new()),
+            fitness_cache: RefCell::new(None),
+            novelty_search: RefCell::new(None),
+            diversity_fn: None,
             _marker: PhantomData,
         }
     }
 
+    // phenotypes with a cached score, like the elite that is bred again every generation, are not scored again
+    pub fn with_fitness_cache(self, capacity: usize) -> Self
+    where
+        Pheno: Fingerprint,
+    {
+        self.fitness_cache
+            .replace(Some(FitnessCache::new(capacity)));
+        self
+    }
+
+    // measures the diversity of every generation as the mean distance between two of its phenotypes for the
+    // min_diversity termination criterion, which takes time quadratic in the size of the generation
+    pub fn with_diversity_measure(mut self) -> Self
+    where
+        Pheno: PhenotypeDistance,
+    {
+        self.diversity_fn = Some(mean_distance);
+        self
+    }
+
+    // selects the parents by the novelty of their behaviour blended with their score, the winner, the hall
+    // of fame, the observers and the termination still go by the score itself
+    pub fn with_novelty_search(
+        self,
+        behaviour_fn: Box<BehaviourFn<Pheno>>,
+        novelty_options: NoveltyOptions,
+    ) -> Self {
+        self.novelty_search
+            .replace(Some(NoveltySearch::new(behaviour_fn, novelty_options)));
+        self
+    }
+
+    pub fn get_novelty_archive(&self) -> Vec<Vec<f64>> {
+        self.novelty_search
+            .borrow()
+            .as_ref()
+            .map_or_else(Vec::new, |novelty_search| {
+                novelty_search.get_archive().to_vec()
+            })
+    }
+
+    pub fn get_cache_stats(&self) -> Option<CacheStats> {
+        self.fitness_cache
+            .borrow()
+            .as_ref()
+            .map(FitnessCache::get_stats)
+    }
+
+    pub fn get_strategy(&self) -> &Strategy {
+        &self.strategy
+    }
+
+    // observers are Send so that island launchers can run their islands on threads
+    pub fn add_observer(&mut self, observer: Box<dyn EvolutionObserver<Pheno> + Send>) {
+        self.observers.get_mut().push(observer);
+    }
+
     pub fn evolve(
         &self,
         evol_options: EvolOptions,
         starting_value: Pheno,
         rng: &mut RandomNumberGenerator,
-    ) -> EvolutionResult<Pheno> {
+    ) -> Result<EvolutionResult<Pheno>, EvolutionError>
+    where
+        Pheno: Fingerprint,
+    {
         let mut evol_coordinator = EvolutionCoordinator::new(&evol_options);
-        let mut candidates: Vec<Pheno> = Vec::new();
-        let mut fitness: Vec<EvolutionResult<Pheno>> = Vec::new();
-        let mut parents: Vec<Pheno> = vec![starting_value];
-
-        for _ in 0..evol_options.get_num_generations() {
-            candidates.clear();
-            evol_coordinator.run();
-            candidates.extend(self.strategy.breed(
-                parents.clone(),
-                rng,
-                evol_coordinator.clone(),
-                &evol_options,
-            ));
-            fitness.clear();
-            for candidate in candidates.iter() {
-                let score = (self.score_fn)(*candidate);
-                fitness.push(EvolutionResult::<Pheno> {
-                    winner: candidate.clone(),
-                    score,
-                });
+        let hall_of_fame = HallOfFame::new(evol_options.get_hall_of_fame_size());
+        self.start_run();
+        self.run(
+            &evol_options,
+            &mut evol_coordinator,
+            RunState {
+                parents: vec![starting_value],
+                fitness: Vec::new(),
+                hall_of_fame,
+            },
+            rng,
+            &mut |_, _, _, _| Ok(()),
+        )
+    }
+
+    // starts from a full initial population instead of a single starting value, the population is scored,
+    // reported to the observers, enters the hall of fame as generation 0 and is passed to the strategy as a
+    // whole, and the first generation is bred from its parents
+    pub fn evolve_initialised(
+        &self,
+        evol_options: EvolOptions,
+        template: &Pheno,
+        initialisation: &Initialisation<Pheno>,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<EvolutionResult<Pheno>, EvolutionError>
+    where
+        Pheno: InitialisablePhenotype + Fingerprint,
+    {
+        let population = initialisation.populate(template, rng);
+        if population.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        let mut evol_coordinator = EvolutionCoordinator::new(&evol_options);
+        self.start_run();
+        let (scores, ranking_scores, num_evaluations) =
+            self.score_generation(&population, &evol_options)?;
+        evol_coordinator.record_scores(&scores, num_evaluations);
+        // the strategy did not breed the initial population, it starts from all of it
+        self.strategy.start_run(&population, &ranking_scores);
+        let fitness = into_fitness(population, scores, ranking_scores);
+        self.report_generation(&fitness, &evol_options, &mut evol_coordinator);
+        let mut hall_of_fame = HallOfFame::new(evol_options.get_hall_of_fame_size());
+        hall_of_fame.record(&fitness, evol_coordinator.get_current_generation());
+        let parents = self.select_parents(&fitness, &evol_options, rng);
+        self.run(
+            &evol_options,
+            &mut evol_coordinator,
+            RunState {
+                parents,
+                fitness,
+                hall_of_fame,
+            },
+            rng,
+            &mut |_, _, _, _| Ok(()),
+        )
+    }
+
+    // the generation loop shared by fresh, checkpointed and resumed runs, the run has to be started
+    fn run(
+        &self,
+        evol_options: &EvolOptions,
+        evol_coordinator: &mut EvolutionCoordinator,
+        state: RunState<Pheno>,
+        rng: &mut RandomNumberGenerator,
+        on_scored: &mut CheckpointFn<'_, Pheno>,
+    ) -> Result<EvolutionResult<Pheno>, EvolutionError> {
+        self.check_termination(evol_options)?;
+        let RunState {
+            mut parents,
+            mut fitness,
+            mut hall_of_fame,
+        } = state;
+        let stop_reason = loop {
+            // at least one generation is needed to have a winner
+            if let Some(stop_reason) = evol_coordinator
+                .get_stop_reason(&evol_options.get_termination())
+                .filter(|_| !fitness.is_empty())
+            {
+                break stop_reason;
             }
-            fitness.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
-            if evol_options.get_log_level() > 0 {
-                println!("Generation: {}", evol_coordinator.get_current_generation());
-                if evol_options.get_log_level() > 1 {
-                    for fit in fitness.iter() {
-                        println!(
-                            "Score {}: Phenotype: {}",
-                            fit.score,
-                            fit.winner.to_string_internal()
-                        );
-                    }
-                }
+            // the first population of a steady state run is bred like a generation
+            fitness = if fitness.is_empty() {
+                self.evolve_generation(&parents, evol_options, evol_coordinator, rng)?
+            } else {
+                self.evolve_steady_state(&parents, fitness, evol_options, evol_coordinator, rng)?
+            };
+            hall_of_fame.record(&fitness, evol_coordinator.get_current_generation());
+            on_scored(evol_coordinator, &fitness, &hall_of_fame, rng)?;
+            parents = self.select_parents(&fitness, evol_options, rng);
+        };
+        let result = EvolutionResult {
+            winner: fitness[0].phenotype.clone(),
+            score: fitness[0].score,
+            stop_reason,
+            hall_of_fame: hall_of_fame.into_entries(),
+        };
+        self.notify_finish(&result);
+        Ok(result)
+    }
+
+    // breeds, scores and sorts one generation, the best phenotype comes first
+    pub fn evolve_generation(
+        &self,
+        parents: &[Pheno],
+        evol_options: &EvolOptions,
+        evol_coordinator: &mut EvolutionCoordinator,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<Fitness<Pheno>>, EvolutionError> {
+        if parents.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        evol_coordinator.run();
+        let candidates =
+            self.strategy
+                .breed(parents.to_vec(), rng, *evol_coordinator, evol_options)?;
+        if candidates.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        let (scores, ranking_scores, num_evaluations) =
+            self.score_generation(&candidates, evol_options)?;
+        evol_coordinator.record_scores(&scores, num_evaluations);
+        // the strategy updates its state by the scores the launcher selects by
+        self.strategy.record_scores(&candidates, &ranking_scores)?;
+        let fitness = into_fitness(candidates, scores, ranking_scores);
+        self.report_generation(&fitness, evol_options, evol_coordinator);
+        Ok(fitness)
+    }
+
+    // the strategy breeds a generation of num_offspring children from the parents, each of them replaces
+    // one member of the sorted population, which is sorted again afterwards; generational runs breed a
+    // whole generation instead
+    pub fn evolve_steady_state(
+        &self,
+        parents: &[Pheno],
+        mut population: Vec<Fitness<Pheno>>,
+        evol_options: &EvolOptions,
+        evol_coordinator: &mut EvolutionCoordinator,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<Fitness<Pheno>>, EvolutionError> {
+        let GenerationModel::SteadyState {
+            num_offspring,
+            replacement,
+        } = evol_options.get_generation_model()
+        else {
+            return self.evolve_generation(parents, evol_options, evol_coordinator, rng);
+        };
+        if parents.is_empty() || population.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        evol_coordinator.run();
+        let offspring = self.strategy.breed(
+            parents.to_vec(),
+            rng,
+            *evol_coordinator,
+            &evol_options.with_generation_size(num_offspring.max(1)),
+        )?;
+        if offspring.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        let (scores, ranking_scores, num_evaluations) =
+            self.score_generation(&offspring, evol_options)?;
+        self.strategy.record_scores(&offspring, &ranking_scores)?;
+        let num_children = offspring.len();
+        for ((phenotype, score), ranking_score) in
+            offspring.into_iter().zip(scores).zip(ranking_scores)
+        {
+            let population_scores: Vec<f64> = population.iter().map(|fit| fit.score).collect();
+            let loser = replacement.select_loser(&population_scores, rng);
+            population[loser] = Fitness {
+                phenotype,
+                score,
+                ranking_score,
+            };
+            sort_fitness(&mut population);
+        }
+        let population_scores: Vec<f64> = population.iter().map(|fit| fit.score).collect();
+        evol_coordinator.record_population(&population_scores, num_children, num_evaluations);
+        self.report_generation(&population, evol_options, evol_coordinator);
+        Ok(population)
+    }
+
+    // scores the candidates of one generation after the NonFiniteScorePolicy, returns the scores and the
+    // ranking scores of the novelty search in the order of the candidates and the number of evaluations
+    // of the score function
+    fn score_generation(
+        &self,
+        candidates: &[Pheno],
+        evol_options: &EvolOptions,
+    ) -> Result<(Vec<f64>, Vec<f64>, usize), EvolutionError> {
+        let (scores, num_evaluations) = self.score(candidates, evol_options.get_num_threads());
+        let policy = evol_options.get_non_finite_score_policy();
+        let scores = scores
+            .into_iter()
+            .zip(candidates.iter())
+            .map(|(score, candidate)| sanitize_score(policy, score, candidate))
+            .collect::<Result<Vec<f64>, EvolutionError>>()?;
+        let ranking_scores = match self.novelty_search.borrow_mut().as_mut() {
+            Some(novelty_search) => {
+                let behaviours = self.scorer.evaluate(
+                    &candidates.iter().collect::<Vec<&Pheno>>(),
+                    evol_options.get_num_threads(),
+                    novelty_search.get_behaviour_fn(),
+                );
+                novelty_search.rank(&behaviours, &scores)
+            }
+            None => scores.clone(),
+        };
+        Ok((scores, ranking_scores, num_evaluations))
+    }
+
+    // tells the observers about the sorted fitness of a generation, any of them can end the run
+    fn report_generation(
+        &self,
+        fitness: &[Fitness<Pheno>],
+        evol_options: &EvolOptions,
+        evol_coordinator: &mut EvolutionCoordinator,
+    ) {
+        self.measure_diversity(fitness, evol_coordinator);
+        let report = GenerationReport {
+            generation: evol_coordinator.get_current_generation(),
+            progress: evol_coordinator.get_progress(),
+            fitness,
+        };
+        let mut action = LogObserver::new(evol_options.get_log_level()).on_generation(&report);
+        for observer in self.observers.borrow_mut().iter_mut() {
+            if observer.on_generation(&report) == ObserverAction::Stop {
+                action = ObserverAction::Stop;
             }
-            parents.clear();
-            let mut i = 0;
-            for fit in fitness.iter() {
-                parents.push(fit.winner.clone());
-                if i >= evol_options.get_num_parents() {
-                    break;
+        }
+        if action == ObserverAction::Stop {
+            evol_coordinator.cancel();
+        }
+    }
+
+    fn measure_diversity(
+        &self,
+        fitness: &[Fitness<Pheno>],
+        evol_coordinator: &mut EvolutionCoordinator,
+    ) {
+        if let Some(diversity_fn) = self.diversity_fn {
+            evol_coordinator.record_diversity(diversity_fn(fitness));
+        }
+    }
+
+    // the min_diversity termination criterion can not be met by a launcher that does not measure the
+    // diversity
+    pub fn check_termination(&self, evol_options: &EvolOptions) -> Result<(), EvolutionError> {
+        if evol_options.get_termination().get_min_diversity().is_some()
+            && self.diversity_fn.is_none()
+        {
+            return Err(EvolutionError::InvalidOptions(
+                "the minimum diversity needs a launcher with a diversity measure".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // the scores in the order of the candidates and the number of evaluations of the score function,
+    // without a fitness cache every candidate is evaluated
+    fn score(&self, candidates: &[Pheno], num_threads: usize) -> (Vec<f64>, usize) {
+        let mut fitness_cache = self.fitness_cache.borrow_mut();
+        let Some(cache) = fitness_cache.as_mut() else {
+            let candidates: Vec<&Pheno> = candidates.iter().collect();
+            return (
+                self.scorer.score(&candidates, num_threads),
+                candidates.len(),
+            );
+        };
+        // every distinct candidate is looked up once and, without a cached score, evaluated once
+        let mut lookups: HashMap<u64, Option<f64>> = HashMap::new();
+        let mut missing: Vec<(u64, &Pheno)> = Vec::new();
+        let mut fingerprints = Vec::with_capacity(candidates.len());
+        for candidate in candidates.iter() {
+            let fingerprint = cache.fingerprint(candidate);
+            lookups.entry(fingerprint).or_insert_with(|| {
+                let score = cache.get(fingerprint);
+                if score.is_none() {
+                    missing.push((fingerprint, candidate));
                 }
-                i += 1;
+                score
+            });
+            fingerprints.push(fingerprint);
+        }
+        let missing_candidates: Vec<&Pheno> =
+            missing.iter().map(|&(_, candidate)| candidate).collect();
+        let missing_scores = self.scorer.score(&missing_candidates, num_threads);
+        for (&(fingerprint, _), &score) in missing.iter().zip(missing_scores.iter()) {
+            cache.insert(fingerprint, score);
+            lookups.insert(fingerprint, Some(score));
+        }
+        let scores = fingerprints
+            .iter()
+            .map(|fingerprint| lookups[fingerprint].expect("every missing score was evaluated"))
+            .collect();
+        (scores, missing.len())
+    }
+
+    // empties the novelty archive, resets the strategy and tells the observers, every run of the launcher
+    // starts with it
+    pub fn start_run(&self) {
+        self.strategy.start_run(&[], &[]);
+        if let Some(novelty_search) = self.novelty_search.borrow_mut().as_mut() {
+            novelty_search.clear_archive();
+        }
+        for observer in self.observers.borrow_mut().iter_mut() {
+            observer.on_start();
+        }
+    }
+
+    pub fn notify_finish(&self, result: &EvolutionResult<Pheno>) {
+        for observer in self.observers.borrow_mut().iter_mut() {
+            observer.on_finish(result);
+        }
+    }
+
+    pub fn select_parents(
+        &self,
+        fitness: &[Fitness<Pheno>],
+        evol_options: &EvolOptions,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<Pheno> {
+        // the fitness is sorted by score, the selection needs it sorted by ranking score; the sort is
+        // stable, without a novelty search the order does not change
+        let mut ranking: Vec<&Fitness<Pheno>> = fitness.iter().collect();
+        ranking.sort_by(|a, b| b.ranking_score.total_cmp(&a.ranking_score));
+        let ranking_scores: Vec<f64> = ranking.iter().map(|fit| fit.ranking_score).collect();
+        evol_options
+            .get_selection()
+            .select(&ranking_scores, evol_options.get_num_parents(), rng)
+            .into_iter()
+            .map(|i| ranking[i].phenotype.clone())
+            .collect()
+    }
+}
+
+// where a run picks up, a fresh run has no fitness yet
+struct RunState<Pheno: Phenotype> {
+    parents: Vec<Pheno>,
+    fitness: Vec<Fitness<Pheno>>,
+    hall_of_fame: HallOfFame<Pheno>,
+}
+
+// measures the diversity of a generation
+type DiversityFn<Pheno> = fn(&[Fitness<Pheno>]) -> f64;
+
+type CheckpointFn<'a, Pheno> = dyn FnMut(
+        &EvolutionCoordinator,
+        &[Fitness<Pheno>],
+        &HallOfFame<Pheno>,
+        &RandomNumberGenerator,
+    ) -> io::Result<()>
+    + 'a;
+
+impl<Pheno, EvolOptions, Strategy, Scoring> EvolutionLauncher<Pheno, EvolOptions, Strategy, Scoring>
+where
+    Pheno: Phenotype + Checkpointable + Fingerprint,
+    EvolOptions: EvolutionOptionsTrait + Checkpointable,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
+{
+    // writes a checkpoint every checkpoint_interval generations together with the state of the strategy, a
+    // seeded run can be resumed bit identically
+    pub fn evolve_with_checkpoints(
+        &self,
+        evol_options: EvolOptions,
+        starting_value: Pheno,
+        rng: &mut RandomNumberGenerator,
+        checkpoint_path: &Path,
+        checkpoint_interval: usize,
+    ) -> Result<EvolutionResult<Pheno>, EvolutionError> {
+        let mut evol_coordinator = EvolutionCoordinator::new(&evol_options);
+        let hall_of_fame = HallOfFame::new(evol_options.get_hall_of_fame_size());
+        let mut checkpoint_writer =
+            self.checkpoint_writer(&evol_options, checkpoint_path, checkpoint_interval);
+        self.start_run();
+        self.run(
+            &evol_options,
+            &mut evol_coordinator,
+            RunState {
+                parents: vec![starting_value],
+                fitness: Vec::new(),
+                hall_of_fame,
+            },
+            rng,
+            &mut checkpoint_writer,
+        )
+    }
+
+    // continues the run stored in checkpoint_path and keeps writing checkpoints to it
+    pub fn resume(
+        &self,
+        checkpoint_path: &Path,
+        checkpoint_interval: usize,
+    ) -> Result<EvolutionResult<Pheno>, EvolutionError> {
+        let checkpoint = Checkpoint::<Pheno, EvolOptions>::load(checkpoint_path)?;
+        let evol_options = checkpoint.evol_options;
+        let mut rng = checkpoint.rng;
+        let mut evol_coordinator = checkpoint.evol_coordinator;
+        self.start_run();
+        if let Some(novelty_search) = self.novelty_search.borrow_mut().as_mut() {
+            novelty_search.restore_archive(checkpoint.novelty_archive);
+        }
+        self.strategy
+            .load_state(checkpoint.strategy_state.as_deref())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid strategy state in {}", checkpoint_path.display()),
+                )
+            })?;
+        self.measure_diversity(&checkpoint.fitness, &mut evol_coordinator);
+        let parents = self.select_parents(&checkpoint.fitness, &evol_options, &mut rng);
+        let hall_of_fame = HallOfFame::from_entries(
+            evol_options.get_hall_of_fame_size(),
+            checkpoint.hall_of_fame,
+        );
+        let mut checkpoint_writer =
+            self.checkpoint_writer(&evol_options, checkpoint_path, checkpoint_interval);
+        self.run(
+            &evol_options,
+            &mut evol_coordinator,
+            RunState {
+                parents,
+                fitness: checkpoint.fitness,
+                hall_of_fame,
+            },
+            &mut rng,
+            &mut checkpoint_writer,
+        )
+    }
+
+    // saves the run every checkpoint_interval generations to checkpoint_path, 0 never saves
+    fn checkpoint_writer<'a>(
+        &'a self,
+        evol_options: &'a EvolOptions,
+        checkpoint_path: &'a Path,
+        checkpoint_interval: usize,
+    ) -> impl FnMut(
+        &EvolutionCoordinator,
+        &[Fitness<Pheno>],
+        &HallOfFame<Pheno>,
+        &RandomNumberGenerator,
+    ) -> io::Result<()>
+           + 'a {
+        move |evol_coordinator, fitness, hall_of_fame, rng| {
+            let generation = evol_coordinator.get_current_generation();
+            if checkpoint_interval == 0 || generation % checkpoint_interval != 0 {
+                return Ok(());
+            }
+            Checkpoint {
+                evol_coordinator: *evol_coordinator,
+                evol_options: evol_options.clone(),
+                rng: rng.clone(),
+                fitness: fitness.to_vec(),
+                hall_of_fame: hall_of_fame.get_entries().to_vec(),
+                novelty_archive: self.get_novelty_archive(),
+                strategy_state: self.strategy.save_state(),
             }
+            .save(checkpoint_path)
         }
-        fitness[0].clone()
     }
 }
 
+// applies the NonFiniteScorePolicy to the score of phenotype
+pub(crate) fn sanitize_score<Pheno: Phenotype>(
+    policy: NonFiniteScorePolicy,
+    score: f64,
+    phenotype: &Pheno,
+) -> Result<f64, EvolutionError> {
+    policy
+        .apply(score)
+        .ok_or_else(|| EvolutionError::NonFiniteScore {
+            phenotype: phenotype.to_string_internal(),
+            score,
+        })
+}
+
+// pairs the candidates with their scores, the best score comes first
+fn into_fitness<Pheno: Phenotype>(
+    candidates: Vec<Pheno>,
+    scores: Vec<f64>,
+    ranking_scores: Vec<f64>,
+) -> Vec<Fitness<Pheno>> {
+    let mut fitness: Vec<Fitness<Pheno>> = candidates
+        .into_iter()
+        .zip(scores.into_iter().zip(ranking_scores))
+        .map(|(phenotype, (score, ranking_score))| Fitness {
+            phenotype,
+            score,
+            ranking_score,
+        })
+        .collect();
+    sort_fitness(&mut fitness);
+    fitness
+}
+
+// the mean distance between two different members, 0 for fewer than two members
+fn mean_distance<Pheno: Phenotype + PhenotypeDistance>(fitness: &[Fitness<Pheno>]) -> f64 {
+    let num_pairs = fitness.len() * fitness.len().saturating_sub(1) / 2;
+    if num_pairs == 0 {
+        return 0.0;
+    }
+    let total: f64 = fitness
+        .iter()
+        .enumerate()
+        .flat_map(|(i, first)| {
+            fitness[i + 1..]
+                .iter()
+                .map(|second| first.phenotype.distance(&second.phenotype))
+        })
+        .sum();
+    total / num_pairs as f64
+}
+
+pub fn sort_fitness<Pheno: Phenotype>(fitness: &mut [Fitness<Pheno>]) {
+    fitness.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
     use crate::evol::{
+        error::EvolutionError,
         evol_options::{EvolutionOptions, PartialEvolutionOptions},
+        generation_model::{GenerationModel, Replacement},
+        genomes::bit_string::BitString,
+        observer::{EvolutionObserver, GenerationReport, ObserverAction},
         ordinary_evol_strategy::OrdinaryEvolutionStrategy,
         partial_evol_strategy::PartialEvolutionStrategy,
         rand::RandomNumberGenerator,
-        test_evol::{XCoordinate, XCoordinateChallenge},
+        score_policy::NonFiniteScorePolicy,
+        scorer::ParallelScorer,
+        selection::Selection,
+        termination::{StopReason, TerminationCriteria},
+        test_evol::{XCoordinate, XCoordinateChallenge, XCoordinates, XCoordinatesChallenge},
+        traits::EvolutionOptionsTrait,
     };
 
-    use super::EvolutionLauncher;
+    use super::{EvolutionLauncher, EvolutionResult};
+
+    // records the x of every member of every generation
+    struct PopulationObserver {
+        populations: Arc<Mutex<Vec<Vec<u64>>>>,
+    }
+
+    impl EvolutionObserver<XCoordinate> for PopulationObserver {
+        fn on_generation(&mut self, report: &GenerationReport<XCoordinate>) -> ObserverAction {
+            let population = report
+                .fitness
+                .iter()
+                .map(|fit| fit.phenotype.x().to_bits())
+                .collect();
+            self.populations.lock().unwrap().push(population);
+            ObserverAction::Continue
+        }
+    }
 
     #[test]
     fn test_ordinary() {
-        let mut rng = RandomNumberGenerator::new();
+        let mut rng = RandomNumberGenerator::from_seed(0);
         let starting_value = XCoordinate::new(0.0);
         let evol_options = EvolutionOptions::new();
         let strategy = OrdinaryEvolutionStrategy;
@@ -114,15 +750,17 @@ mod tests {
         let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
             EvolutionLauncher::new(
                 strategy,
-                Box::new(move |phenotype: XCoordinate| challenge.score(phenotype)),
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
             );
-        let winner = launcher.evolve(evol_options, starting_value, &mut rng);
+        let winner = launcher
+            .evolve(evol_options, starting_value, &mut rng)
+            .unwrap();
         assert!((winner.winner.x() - 2.0).abs() < 1e-2);
     }
 
     #[test]
     fn test_partial() {
-        let mut rng = RandomNumberGenerator::new();
+        let mut rng = RandomNumberGenerator::from_seed(0);
         let starting_value = XCoordinate::new(7.0);
         let evol_options = PartialEvolutionOptions::new(EvolutionOptions::new(), 3.0, 10.0);
         let strategy = PartialEvolutionStrategy;
@@ -133,9 +771,521 @@ mod tests {
             PartialEvolutionStrategy,
         > = EvolutionLauncher::new(
             strategy,
-            Box::new(move |phenotype: XCoordinate| challenge.score(phenotype)),
+            Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
         );
-        let winner = launcher.evolve(evol_options, starting_value, &mut rng);
+        let winner = launcher
+            .evolve(evol_options, starting_value, &mut rng)
+            .unwrap();
         assert!((winner.winner.x() - 3.0).abs() < 1e-2);
     }
+
+    #[test]
+    fn test_same_seed_same_winner() {
+        let evolve = |seed: u64| {
+            let mut rng = RandomNumberGenerator::from_seed(seed);
+            let challenge = XCoordinateChallenge::new(2.0);
+            let launcher: EvolutionLauncher<
+                XCoordinate,
+                EvolutionOptions,
+                OrdinaryEvolutionStrategy,
+            > = EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+            );
+            launcher
+                .evolve(EvolutionOptions::new(), XCoordinate::new(0.0), &mut rng)
+                .unwrap()
+        };
+        let first = evolve(1234);
+        let second = evolve(1234);
+        assert_eq!(first.winner.x().to_bits(), second.winner.x().to_bits());
+        assert_eq!(first.score.to_bits(), second.score.to_bits());
+    }
+
+    #[test]
+    fn test_parallel_same_as_serial() {
+        let evol_options = EvolutionOptions::new().with_num_threads(4);
+        let challenge = XCoordinateChallenge::new(2.0);
+        let serial_launcher: EvolutionLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+        );
+        let mut rng = RandomNumberGenerator::from_seed(99);
+        let serial = serial_launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        let challenge = XCoordinateChallenge::new(2.0);
+        let parallel_launcher: EvolutionLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+            ParallelScorer<XCoordinate, f64>,
+        > = EvolutionLauncher::new_parallel(
+            OrdinaryEvolutionStrategy,
+            Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+        );
+        // the second run scores on the threads of the first
+        for _ in 0..2 {
+            let mut rng = RandomNumberGenerator::from_seed(99);
+            let parallel = parallel_launcher
+                .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+                .unwrap();
+            assert_eq!(serial.winner.x().to_bits(), parallel.winner.x().to_bits());
+            assert_eq!(serial.score.to_bits(), parallel.score.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_tournament_selection() {
+        let mut rng = RandomNumberGenerator::from_seed(5);
+        let evol_options = EvolutionOptions::new()
+            .with_num_parents(4)
+            .with_selection(Selection::Tournament(3));
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+            );
+        let winner = launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        assert!((winner.winner.x() - 2.0).abs() < 1e-1);
+    }
+
+    #[test]
+    fn test_heap_allocated_phenotype() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let starting_value = XCoordinates::new(vec![0.0, 0.0, 0.0]);
+        let challenge = XCoordinatesChallenge::new(vec![1.0, -2.0, 3.0]);
+        let launcher: EvolutionLauncher<XCoordinates, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinates| challenge.score(phenotype)),
+            );
+        let winner = launcher
+            .evolve(EvolutionOptions::new(), starting_value, &mut rng)
+            .unwrap();
+        for (x, target) in winner.winner.xs().iter().zip([1.0, -2.0, 3.0]) {
+            assert!((x - target).abs() < 1e-1);
+        }
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint_path =
+            std::env::temp_dir().join(format!("evol_checkpoint_{}.txt", std::process::id()));
+        // a file next to the checkpoint that only differs in the extension is left alone
+        let neighbour_path = checkpoint_path.with_extension("tmp");
+        std::fs::write(&neighbour_path, "neighbour").unwrap();
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> = {
+            let interrupted = interrupted.clone();
+            let evaluations = evaluations.clone();
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| {
+                    // the NaN is rejected and ends the run in generation 75, like a crash would
+                    let evaluation = evaluations.fetch_add(1, Ordering::Relaxed);
+                    if interrupted.load(Ordering::Relaxed) && evaluation >= 75 * 20 {
+                        return f64::NAN;
+                    }
+                    challenge.score(phenotype)
+                }),
+            )
+        };
+        let evol_options = EvolutionOptions::new()
+            .with_selection(Selection::Tournament(2))
+            .with_non_finite_score_policy(NonFiniteScorePolicy::Reject);
+        let mut rng = RandomNumberGenerator::from_seed(11);
+        let uninterrupted = launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        interrupted.store(true, Ordering::Relaxed);
+        evaluations.store(0, Ordering::Relaxed);
+        let mut rng = RandomNumberGenerator::from_seed(11);
+        let result = launcher.evolve_with_checkpoints(
+            evol_options,
+            XCoordinate::new(0.0),
+            &mut rng,
+            &checkpoint_path,
+            30,
+        );
+        assert!(matches!(result, Err(EvolutionError::NonFiniteScore { .. })));
+        // the run continues from the checkpoint of generation 60
+        interrupted.store(false, Ordering::Relaxed);
+        let resumed = launcher.resume(&checkpoint_path, 30).unwrap();
+        std::fs::remove_file(&checkpoint_path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&neighbour_path).unwrap(),
+            "neighbour"
+        );
+        std::fs::remove_file(&neighbour_path).unwrap();
+        assert_eq!(
+            uninterrupted.winner.x().to_bits(),
+            resumed.winner.x().to_bits()
+        );
+        assert_eq!(uninterrupted.score.to_bits(), resumed.score.to_bits());
+        let hall_of_fame = |result: &EvolutionResult<XCoordinate>| -> Vec<(u64, usize)> {
+            result
+                .hall_of_fame
+                .iter()
+                .map(|entry| (entry.score.to_bits(), entry.generation))
+                .collect()
+        };
+        assert_eq!(hall_of_fame(&uninterrupted), hall_of_fame(&resumed));
+    }
+
+    #[test]
+    fn test_checkpoint_version() {
+        let checkpoint_path =
+            std::env::temp_dir().join(format!("evol_version_{}.txt", std::process::id()));
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        launcher
+            .evolve_with_checkpoints(
+                EvolutionOptions::new().with_num_generations(10),
+                XCoordinate::new(0.0),
+                &mut rng,
+                &checkpoint_path,
+                10,
+            )
+            .unwrap();
+        let content = std::fs::read_to_string(&checkpoint_path).unwrap();
+        assert!(content.starts_with("version 3\n"));
+        // a checkpoint written before the format had a version is rejected
+        let (_, unversioned) = content.split_once('\n').unwrap();
+        std::fs::write(&checkpoint_path, unversioned).unwrap();
+        let result = launcher.resume(&checkpoint_path, 10);
+        std::fs::remove_file(&checkpoint_path).unwrap();
+        assert!(matches!(result, Err(EvolutionError::Checkpoint(_))));
+    }
+
+    #[test]
+    fn test_hall_of_fame() {
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new()
+            .with_selection(Selection::Tournament(2))
+            .with_hall_of_fame_size(5);
+        let result = launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        assert_eq!(result.hall_of_fame.len(), 5);
+        assert!(result.hall_of_fame[0].score >= result.score);
+        for (better, worse) in result
+            .hall_of_fame
+            .iter()
+            .zip(result.hall_of_fame.iter().skip(1))
+        {
+            assert!(better.score >= worse.score);
+            assert_ne!(better.phenotype.x(), worse.phenotype.x());
+        }
+        assert!(result
+            .hall_of_fame
+            .iter()
+            .all(|entry| (1..=evol_options.get_num_generations()).contains(&entry.generation)));
+    }
+
+    #[test]
+    fn test_termination() {
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+            );
+        let evolve = |termination: TerminationCriteria| {
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            let evol_options = EvolutionOptions::new().with_termination(termination);
+            launcher
+                .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+                .unwrap()
+        };
+        let result = evolve(TerminationCriteria::new());
+        assert_eq!(result.stop_reason, StopReason::MaxGenerations);
+        let result = evolve(TerminationCriteria::new().with_target_score(100.0));
+        assert_eq!(result.stop_reason, StopReason::TargetScoreReached);
+        assert!(result.score >= 100.0);
+        let result = evolve(TerminationCriteria::new().with_max_evaluations(100));
+        assert_eq!(result.stop_reason, StopReason::MaxEvaluationsReached);
+        let result = evolve(TerminationCriteria::new().with_max_stagnation(1));
+        assert_eq!(result.stop_reason, StopReason::Stagnation);
+        let result = evolve(TerminationCriteria::new().with_time_budget(Duration::ZERO));
+        assert_eq!(result.stop_reason, StopReason::TimeBudgetExhausted);
+    }
+
+    #[test]
+    fn test_low_score_spread() {
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(|phenotype: &XCoordinate| -(phenotype.x() - 2.0).powi(2)),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new()
+            .with_termination(TerminationCriteria::new().with_min_score_spread(1e-3));
+        let result = launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        // the children of a converged population score alike
+        assert_eq!(result.stop_reason, StopReason::LowScoreSpread);
+        assert!((result.winner.x() - 2.0).abs() < 1e-1);
+    }
+
+    #[test]
+    fn test_low_diversity() {
+        let new_launcher =
+            || -> EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> {
+                EvolutionLauncher::new(
+                    OrdinaryEvolutionStrategy,
+                    Box::new(|phenotype: &XCoordinate| -(phenotype.x() - 2.0).powi(2)),
+                )
+            };
+        let evol_options = EvolutionOptions::new()
+            .with_termination(TerminationCriteria::new().with_min_diversity(0.05));
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = new_launcher().evolve(evol_options, XCoordinate::new(0.0), &mut rng);
+        assert!(matches!(result, Err(EvolutionError::InvalidOptions(_))));
+        let result = new_launcher()
+            .with_diversity_measure()
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        // the children of a converged population lie close together
+        assert_eq!(result.stop_reason, StopReason::LowDiversity);
+        assert!((result.winner.x() - 2.0).abs() < 1e-1);
+    }
+
+    #[test]
+    fn test_errors() {
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(|phenotype: &XCoordinate| phenotype.x().sqrt()),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        // the square root of the negative x of some children is NaN
+        let result = launcher.evolve(
+            EvolutionOptions::new().with_non_finite_score_policy(NonFiniteScorePolicy::Reject),
+            XCoordinate::new(0.0),
+            &mut rng,
+        );
+        assert!(matches!(result, Err(EvolutionError::NonFiniteScore { .. })));
+        let result = launcher.evolve(
+            EvolutionOptions::new().with_num_parents(0),
+            XCoordinate::new(1.0),
+            &mut rng,
+        );
+        assert!(matches!(result, Err(EvolutionError::EmptyPopulation)));
+    }
+
+    #[test]
+    fn test_fitness_cache() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let launcher = |evaluations: Arc<AtomicUsize>| {
+            let challenge = XCoordinateChallenge::new(2.0);
+            let launcher: EvolutionLauncher<
+                XCoordinate,
+                EvolutionOptions,
+                OrdinaryEvolutionStrategy,
+            > = EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| {
+                    evaluations.fetch_add(1, Ordering::Relaxed);
+                    challenge.score(phenotype)
+                }),
+            );
+            launcher
+        };
+        let evolve = |launcher: &EvolutionLauncher<_, _, _>| {
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            launcher
+                .evolve(EvolutionOptions::new(), XCoordinate::new(0.0), &mut rng)
+                .unwrap()
+        };
+        let uncached = evolve(&launcher(evaluations.clone()));
+        let uncached_evaluations = evaluations.swap(0, Ordering::Relaxed);
+        let cached_launcher = launcher(evaluations.clone()).with_fitness_cache(1000);
+        let cached = evolve(&cached_launcher);
+        // the elite is never scored twice, the result does not change
+        assert_eq!(uncached.winner.x().to_bits(), cached.winner.x().to_bits());
+        let stats = cached_launcher.get_cache_stats().unwrap();
+        assert_eq!(stats.misses, evaluations.load(Ordering::Relaxed));
+        assert_eq!(stats.hits + stats.misses, uncached_evaluations);
+        assert!(stats.hits >= EvolutionOptions::new().get_num_generations() - 1);
+        assert!(launcher(evaluations).get_cache_stats().is_none());
+    }
+
+    #[test]
+    fn test_fitness_cache_duplicates() {
+        // there are only 16 bit strings of length 4, so most children are duplicates
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let counter = evaluations.clone();
+        let launcher: EvolutionLauncher<BitString, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &BitString| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    phenotype.count_ones() as f64
+                }),
+            )
+            .with_fitness_cache(16);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new().with_num_generations(20);
+        launcher
+            .evolve(evol_options, BitString::new(vec![false; 4]), &mut rng)
+            .unwrap();
+        let stats = launcher.get_cache_stats().unwrap();
+        assert_eq!(stats.misses, evaluations.load(Ordering::Relaxed));
+        assert!(stats.misses <= 16);
+        // a duplicate within a generation is looked up once
+        assert!(stats.hits + stats.misses < 20 * evol_options.get_num_children());
+    }
+
+    #[test]
+    fn test_non_finite_score_policy() {
+        // NaN left of 0, infinite right of 1
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(|phenotype: &XCoordinate| match phenotype.x() {
+                    x if x < 0.0 => f64::NAN,
+                    x if x > 1.0 => f64::INFINITY,
+                    x => x,
+                }),
+            );
+        let evolve = |policy: NonFiniteScorePolicy| {
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            let evol_options = EvolutionOptions::new().with_non_finite_score_policy(policy);
+            launcher.evolve(evol_options, XCoordinate::new(0.5), &mut rng)
+        };
+        assert_eq!(
+            evolve(NonFiniteScorePolicy::NanAsWorst).unwrap().score,
+            f64::INFINITY
+        );
+        let result = evolve(NonFiniteScorePolicy::Clamp).unwrap();
+        assert_eq!(result.score, f64::MAX);
+        assert!(result.winner.x() > 1.0);
+        assert!(evolve(NonFiniteScorePolicy::Reject).is_err());
+    }
+
+    #[test]
+    fn test_steady_state() {
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+            );
+        for replacement in [Replacement::Worst, Replacement::Tournament(3)] {
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            let evol_options = EvolutionOptions::new()
+                .with_num_generations(200)
+                .with_generation_model(GenerationModel::SteadyState {
+                    num_offspring: 2,
+                    replacement,
+                });
+            let result = launcher
+                .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+                .unwrap();
+            assert!((result.winner.x() - 2.0).abs() < 1e-1);
+            // the best member is never replaced, so the winner is the best ever scored
+            assert_eq!(result.score, result.hall_of_fame[0].score);
+        }
+    }
+
+    #[test]
+    fn test_steady_state_offspring() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let counter = evaluations.clone();
+        let challenge = XCoordinateChallenge::new(0.7);
+        let mut launcher: EvolutionLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(move |phenotype: &XCoordinate| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                challenge.score(phenotype)
+            }),
+        );
+        let populations = Arc::new(Mutex::new(Vec::new()));
+        launcher.add_observer(Box::new(PopulationObserver {
+            populations: populations.clone(),
+        }));
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new()
+            .with_num_generations(200)
+            .with_num_parents(1)
+            .with_num_children(10)
+            .with_generation_model(GenerationModel::SteadyState {
+                num_offspring: 1,
+                replacement: Replacement::Worst,
+            });
+        let result = launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        assert!((result.winner.x() - 0.7).abs() < 1e-2);
+        // the first population is bred whole, every later generation breeds a single mutated child that
+        // replaces a single member
+        assert_eq!(evaluations.load(Ordering::Relaxed), 10 + 199);
+        let populations = populations.lock().unwrap();
+        assert_eq!(populations.len(), 200);
+        for pair in populations.windows(2) {
+            let replaced = pair[1]
+                .iter()
+                .filter(|member| !pair[0].contains(member))
+                .count();
+            assert_eq!(replaced, 1);
+        }
+    }
+
+    #[test]
+    fn test_steady_state_partial() {
+        // the offspring are bred by the strategy, so they stay in the magnitude range
+        let challenge = XCoordinateChallenge::new(2.0);
+        let launcher: EvolutionLauncher<
+            XCoordinate,
+            PartialEvolutionOptions,
+            PartialEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            PartialEvolutionStrategy,
+            Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+        );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = PartialEvolutionOptions::new(
+            EvolutionOptions::new()
+                .with_num_generations(200)
+                .with_generation_model(GenerationModel::SteadyState {
+                    num_offspring: 3,
+                    replacement: Replacement::Tournament(3),
+                }),
+            3.0,
+            10.0,
+        );
+        let result = launcher
+            .evolve(evol_options, XCoordinate::new(7.0), &mut rng)
+            .unwrap();
+        assert!((result.winner.x() - 3.0).abs() < 1e-1);
+        for entry in result.hall_of_fame.iter() {
+            assert!((3.0..=10.0).contains(&entry.phenotype.x()));
+        }
+    }
 }
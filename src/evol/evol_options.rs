@@ -1,11 +1,24 @@
-use super::traits::{EvolutionOptionsTrait, PartialEvolutionOptionsTrait};
+use super::{
+    checkpoint::Checkpointable,
+    generation_model::GenerationModel,
+    score_policy::NonFiniteScorePolicy,
+    selection::Selection,
+    termination::TerminationCriteria,
+    traits::{EvolutionOptionsTrait, PartialEvolutionOptionsTrait},
+};
 
 #[derive(Clone, Copy)]
 pub struct EvolutionOptions {
-    num_generations: usize, // the number of generations to cross
-    log_level: usize,       // logging level to see how far the algorithm progressed
-    num_parents: usize,     // the number of parents to grow a new generation
-    num_children: usize,    // the number of phenotypes to breed per generation
+    num_generations: usize,           // the number of generations to cross
+    log_level: usize,                 // logging level to see how far the algorithm progressed
+    num_parents: usize,               // the number of parents to grow a new generation
+    num_children: usize,              // the number of phenotypes to breed per generation
+    num_threads: usize,               // the threads to score with, used by parallel launchers
+    selection: Selection,             // how the parents are chosen from the scored children
+    termination: TerminationCriteria, // conditions that end the run before num_generations
+    non_finite_score_policy: NonFiniteScorePolicy, // how NaN and infinite scores are ranked
+    hall_of_fame_size: usize, // the number of best distinct phenotypes kept over the whole run
+    generation_model: GenerationModel,
 }
 
 impl EvolutionOptions {
@@ -15,8 +28,62 @@ impl EvolutionOptions {
             log_level: 0,
             num_parents: 2,
             num_children: 20,
+            num_threads: 1,
+            selection: Selection::Truncation,
+            termination: TerminationCriteria::new(),
+            non_finite_score_policy: NonFiniteScorePolicy::NanAsWorst,
+            hall_of_fame_size: 10,
+            generation_model: GenerationModel::Generational,
         }
     }
+
+    pub fn with_num_generations(mut self, num_generations: usize) -> EvolutionOptions {
+        self.num_generations = num_generations;
+        self
+    }
+
+    pub fn with_num_parents(mut self, num_parents: usize) -> EvolutionOptions {
+        self.num_parents = num_parents;
+        self
+    }
+
+    pub fn with_num_children(mut self, num_children: usize) -> EvolutionOptions {
+        self.num_children = num_children;
+        self
+    }
+
+    pub fn with_num_threads(mut self, num_threads: usize) -> EvolutionOptions {
+        self.num_threads = num_threads;
+        self
+    }
+
+    pub fn with_selection(mut self, selection: Selection) -> EvolutionOptions {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_termination(mut self, termination: TerminationCriteria) -> EvolutionOptions {
+        self.termination = termination;
+        self
+    }
+
+    pub fn with_non_finite_score_policy(
+        mut self,
+        non_finite_score_policy: NonFiniteScorePolicy,
+    ) -> EvolutionOptions {
+        self.non_finite_score_policy = non_finite_score_policy;
+        self
+    }
+
+    pub fn with_hall_of_fame_size(mut self, hall_of_fame_size: usize) -> EvolutionOptions {
+        self.hall_of_fame_size = hall_of_fame_size;
+        self
+    }
+
+    pub fn with_generation_model(mut self, generation_model: GenerationModel) -> EvolutionOptions {
+        self.generation_model = generation_model;
+        self
+    }
 }
 
 impl EvolutionOptionsTrait for EvolutionOptions {
@@ -35,6 +102,69 @@ impl EvolutionOptionsTrait for EvolutionOptions {
     fn get_num_children(&self) -> usize {
         self.num_children
     }
+
+    fn with_generation_size(&self, num_children: usize) -> Self {
+        self.with_num_children(num_children)
+    }
+
+    fn get_num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    fn get_selection(&self) -> Selection {
+        self.selection
+    }
+
+    fn get_termination(&self) -> TerminationCriteria {
+        self.termination
+    }
+
+    fn get_non_finite_score_policy(&self) -> NonFiniteScorePolicy {
+        self.non_finite_score_policy
+    }
+
+    fn get_hall_of_fame_size(&self) -> usize {
+        self.hall_of_fame_size
+    }
+
+    fn get_generation_model(&self) -> GenerationModel {
+        self.generation_model
+    }
+}
+
+impl Checkpointable for EvolutionOptions {
+    fn to_checkpoint(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {} {} {} {}",
+            self.num_generations,
+            self.log_level,
+            self.num_parents,
+            self.num_children,
+            self.num_threads,
+            self.selection.to_checkpoint(),
+            self.termination.to_checkpoint(),
+            self.non_finite_score_policy.to_checkpoint(),
+            self.hall_of_fame_size,
+            self.generation_model.to_checkpoint()
+        )
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut values = line.split(' ');
+        let mut next_number = || values.next()?.parse::<usize>().ok();
+        Some(EvolutionOptions {
+            num_generations: next_number()?,
+            log_level: next_number()?,
+            num_parents: next_number()?,
+            num_children: next_number()?,
+            num_threads: next_number()?,
+            selection: Selection::from_checkpoint(values.next()?)?,
+            termination: TerminationCriteria::from_checkpoint(values.next()?)?,
+            non_finite_score_policy: NonFiniteScorePolicy::from_checkpoint(values.next()?)?,
+            hall_of_fame_size: values.next()?.parse().ok()?,
+            generation_model: GenerationModel::from_checkpoint(values.next()?)?,
+        })
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -42,6 +172,8 @@ pub struct PartialEvolutionOptions {
     options: EvolutionOptions,
     min_magnitude: f64,
     max_magnitude: f64,
+    max_attempts: usize, // the restarts from the unmutated phenotype before giving up
+    max_mutations_per_attempt: usize, // the mutations per restart to reach the magnitude range
 }
 
 impl PartialEvolutionOptions {
@@ -54,8 +186,20 @@ impl PartialEvolutionOptions {
             options,
             min_magnitude,
             max_magnitude,
+            max_attempts: 1000,
+            max_mutations_per_attempt: 1000,
         }
     }
+
+    pub fn with_retry_budget(
+        mut self,
+        max_attempts: usize,
+        max_mutations_per_attempt: usize,
+    ) -> PartialEvolutionOptions {
+        self.max_attempts = max_attempts;
+        self.max_mutations_per_attempt = max_mutations_per_attempt;
+        self
+    }
 }
 
 impl EvolutionOptionsTrait for PartialEvolutionOptions {
@@ -63,6 +207,13 @@ impl EvolutionOptionsTrait for PartialEvolutionOptions {
         self.options.get_num_children()
     }
 
+    fn with_generation_size(&self, num_children: usize) -> Self {
+        PartialEvolutionOptions {
+            options: self.options.with_num_children(num_children),
+            ..*self
+        }
+    }
+
     fn get_num_parents(&self) -> usize {
         self.options.get_num_parents()
     }
@@ -74,6 +225,30 @@ impl EvolutionOptionsTrait for PartialEvolutionOptions {
     fn get_num_generations(&self) -> usize {
         self.options.get_num_generations()
     }
+
+    fn get_num_threads(&self) -> usize {
+        self.options.get_num_threads()
+    }
+
+    fn get_selection(&self) -> Selection {
+        self.options.get_selection()
+    }
+
+    fn get_termination(&self) -> TerminationCriteria {
+        self.options.get_termination()
+    }
+
+    fn get_non_finite_score_policy(&self) -> NonFiniteScorePolicy {
+        self.options.get_non_finite_score_policy()
+    }
+
+    fn get_hall_of_fame_size(&self) -> usize {
+        self.options.get_hall_of_fame_size()
+    }
+
+    fn get_generation_model(&self) -> GenerationModel {
+        self.options.get_generation_model()
+    }
 }
 
 impl PartialEvolutionOptionsTrait for PartialEvolutionOptions {
@@ -84,4 +259,157 @@ impl PartialEvolutionOptionsTrait for PartialEvolutionOptions {
     fn get_max_magnitude(&self) -> f64 {
         self.max_magnitude
     }
+
+    fn get_max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    fn get_max_mutations_per_attempt(&self) -> usize {
+        self.max_mutations_per_attempt
+    }
+}
+
+impl Checkpointable for PartialEvolutionOptions {
+    fn to_checkpoint(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.min_magnitude,
+            self.max_magnitude,
+            self.max_attempts,
+            self.max_mutations_per_attempt,
+            self.options.to_checkpoint()
+        )
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut values = line.splitn(5, ' ');
+        Some(PartialEvolutionOptions {
+            min_magnitude: values.next()?.parse().ok()?,
+            max_magnitude: values.next()?.parse().ok()?,
+            max_attempts: values.next()?.parse().ok()?,
+            max_mutations_per_attempt: values.next()?.parse().ok()?,
+            options: EvolutionOptions::from_checkpoint(values.next()?)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MigrationTopology {
+    Ring,           // every island sends its migrants to the next island
+    FullyConnected, // every island sends its migrants to all other islands
+    Random,         // every island sends its migrants to one randomly chosen island
+}
+
+#[derive(Clone, Copy)]
+pub struct IslandOptions {
+    migration_interval: usize, // the number of generations between two migrations
+    num_migrants: usize,       // the number of best phenotypes sent by each island
+    topology: MigrationTopology,
+}
+
+impl IslandOptions {
+    pub fn new(
+        migration_interval: usize,
+        num_migrants: usize,
+        topology: MigrationTopology,
+    ) -> IslandOptions {
+        IslandOptions {
+            migration_interval,
+            num_migrants,
+            topology,
+        }
+    }
+
+    pub fn get_migration_interval(&self) -> usize {
+        self.migration_interval
+    }
+
+    pub fn get_num_migrants(&self) -> usize {
+        self.num_migrants
+    }
+
+    pub fn get_topology(&self) -> MigrationTopology {
+        self.topology
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct NoveltyOptions {
+    num_neighbours: usize, // the k of the k nearest neighbours a novelty is the mean distance to
+    objective_weight: f64, // 0 ranks by novelty alone, 1 by the score alone
+    num_archived: usize,   // the number of most novel candidates archived every generation
+    max_archive_size: usize, // the oldest behaviours are dropped beyond it
+}
+
+impl NoveltyOptions {
+    pub fn new(num_neighbours: usize, objective_weight: f64) -> NoveltyOptions {
+        NoveltyOptions {
+            num_neighbours,
+            objective_weight,
+            num_archived: 2,
+            max_archive_size: 1000,
+        }
+    }
+
+    pub fn with_archive(mut self, num_archived: usize, max_archive_size: usize) -> NoveltyOptions {
+        self.num_archived = num_archived;
+        self.max_archive_size = max_archive_size;
+        self
+    }
+
+    pub fn get_num_neighbours(&self) -> usize {
+        self.num_neighbours
+    }
+
+    pub fn get_objective_weight(&self) -> f64 {
+        self.objective_weight
+    }
+
+    pub fn get_num_archived(&self) -> usize {
+        self.num_archived
+    }
+
+    pub fn get_max_archive_size(&self) -> usize {
+        self.max_archive_size
+    }
+}
+
+#[derive(Clone)]
+pub struct MapElitesOptions {
+    feature_bounds: Vec<(f64, f64)>, // the range of every feature, features beyond it fall into the edge cells
+    num_cells: Vec<usize>,           // the number of cells along every feature
+    crossover_rate: f64,             // the probability of a child to cross over with a second elite
+}
+
+impl MapElitesOptions {
+    pub fn new(feature_bounds: Vec<(f64, f64)>, num_cells_per_feature: usize) -> MapElitesOptions {
+        let num_cells = vec![num_cells_per_feature; feature_bounds.len()];
+        MapElitesOptions {
+            feature_bounds,
+            num_cells,
+            crossover_rate: 0.5,
+        }
+    }
+
+    pub fn with_num_cells(mut self, num_cells: Vec<usize>) -> MapElitesOptions {
+        self.num_cells = num_cells;
+        self
+    }
+
+    pub fn with_crossover_rate(mut self, crossover_rate: f64) -> MapElitesOptions {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+
+    pub fn get_feature_bounds(&self) -> &[(f64, f64)] {
+        &self.feature_bounds
+    }
+
+    pub fn get_num_cells(&self) -> &[usize] {
+        &self.num_cells
+    }
+
+    pub fn get_crossover_rate(&self) -> f64 {
+        self.crossover_rate
+    }
 }
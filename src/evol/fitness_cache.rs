@@ -0,0 +1,185 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+};
+
+use super::traits::{Fingerprint, Phenotype};
+
+// a deterministic fingerprint of anything hashable, for implementing Fingerprint
+pub fn fingerprint_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = FnvHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// the 64 bit FNV-1a hash; unlike DefaultHasher its algorithm is fixed, so fingerprints and the seeds
+// derived from them stay the same across Rust releases, integers are hashed as little endian 64 bits
+// so that they also stay the same across platforms
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize, // every miss is one evaluation of the score function
+    pub size: usize,
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / lookups as f64
+    }
+}
+
+// remembers the scores of up to capacity phenotypes by their fingerprint, the least recently used
+// score is evicted first
+pub struct FitnessCache<Pheno: Phenotype> {
+    capacity: usize,
+    fingerprint_fn: fn(&Pheno) -> u64,
+    scores: HashMap<u64, (f64, u64)>, // the score and the last use of every fingerprint
+    last_uses: BTreeMap<u64, u64>,    // the fingerprint of every last use, oldest first
+    clock: u64,
+    hits: usize,
+    misses: usize,
+}
+
+impl<Pheno: Phenotype + Fingerprint> FitnessCache<Pheno> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            fingerprint_fn: Pheno::fingerprint,
+            scores: HashMap::new(),
+            last_uses: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<Pheno: Phenotype> FitnessCache<Pheno> {
+    pub fn fingerprint(&self, phenotype: &Pheno) -> u64 {
+        (self.fingerprint_fn)(phenotype)
+    }
+
+    // counts a hit or a miss
+    pub fn get(&mut self, fingerprint: u64) -> Option<f64> {
+        self.clock += 1;
+        match self.scores.get_mut(&fingerprint) {
+            Some((score, last_use)) => {
+                self.last_uses.remove(last_use);
+                self.last_uses.insert(self.clock, fingerprint);
+                *last_use = self.clock;
+                self.hits += 1;
+                Some(*score)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, fingerprint: u64, score: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if let Some((_, last_use)) = self.scores.insert(fingerprint, (score, self.clock)) {
+            self.last_uses.remove(&last_use);
+        }
+        self.last_uses.insert(self.clock, fingerprint);
+        while self.scores.len() > self.capacity {
+            let (_, oldest) = self
+                .last_uses
+                .pop_first()
+                .expect("every cached score has a last use");
+            self.scores.remove(&oldest);
+        }
+    }
+
+    pub fn get_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.scores.len(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+
+    use crate::evol::{test_evol::XCoordinate, traits::Fingerprint};
+
+    use super::{fingerprint_of, FitnessCache, FnvHasher};
+
+    #[test]
+    fn test_least_recently_used() {
+        let mut cache = FitnessCache::<XCoordinate>::new(2);
+        let [a, b, c] = [1.0, 2.0, 3.0].map(|x| XCoordinate::new(x).fingerprint());
+        assert_eq!(cache.get(a), None);
+        cache.insert(a, 1.0);
+        cache.insert(b, 2.0);
+        assert_eq!(cache.get(a), Some(1.0));
+        // b is the least recently used
+        cache.insert(c, 3.0);
+        assert_eq!(cache.get(b), None);
+        assert_eq!(cache.get(a), Some(1.0));
+        assert_eq!(cache.get(c), Some(3.0));
+        let stats = cache.get_stats();
+        assert_eq!((stats.hits, stats.misses, stats.size), (3, 2, 2));
+        assert!((stats.hit_rate() - 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fnv_hasher() {
+        // the reference values of FNV-1a
+        let mut hasher = FnvHasher::default();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fingerprint_of(&1usize), fingerprint_of(&1u64));
+    }
+}
@@ -0,0 +1,116 @@
+use super::{checkpoint::Checkpointable, rand::RandomNumberGenerator};
+
+// which member of a steady state population an offspring replaces, the best member is never replaced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Replacement {
+    Worst,
+    Tournament(usize), // the worst of this many randomly drawn members
+}
+
+impl Replacement {
+    // scores are sorted descending, returns the index of the member to replace
+    pub fn select_loser(&self, scores: &[f64], rng: &mut RandomNumberGenerator) -> usize {
+        if scores.len() < 2 {
+            return 0;
+        }
+        match *self {
+            Replacement::Worst => scores.len() - 1,
+            Replacement::Tournament(tournament_size) => (0..tournament_size.max(1))
+                .map(|_| 1 + rng.fetch_index(scores.len() - 1))
+                .max()
+                .expect("a tournament has at least one contestant"),
+        }
+    }
+}
+
+// how the population moves on from one generation to the next; the IslandLauncher only evolves
+// generationally and rejects the steady state model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GenerationModel {
+    Generational, // num_children children are bred and replace the whole population
+    // the strategy breeds the first population of num_children members, every generation after that
+    // breeds num_offspring children from parents picked by the selection and each of them replaces one member
+    SteadyState {
+        num_offspring: usize,
+        replacement: Replacement,
+    },
+}
+
+impl Checkpointable for GenerationModel {
+    fn to_checkpoint(&self) -> String {
+        match *self {
+            GenerationModel::Generational => "Generational".to_string(),
+            GenerationModel::SteadyState {
+                num_offspring,
+                replacement: Replacement::Worst,
+            } => format!("SteadyState:{}:Worst", num_offspring),
+            GenerationModel::SteadyState {
+                num_offspring,
+                replacement: Replacement::Tournament(tournament_size),
+            } => format!(
+                "SteadyState:{}:Tournament:{}",
+                num_offspring, tournament_size
+            ),
+        }
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut values = line.split(':');
+        match values.next()? {
+            "Generational" => Some(GenerationModel::Generational),
+            "SteadyState" => {
+                let num_offspring = values.next()?.parse().ok()?;
+                let replacement = match values.next()? {
+                    "Worst" => Replacement::Worst,
+                    "Tournament" => Replacement::Tournament(values.next()?.parse().ok()?),
+                    _ => return None,
+                };
+                Some(GenerationModel::SteadyState {
+                    num_offspring,
+                    replacement,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{checkpoint::Checkpointable, rand::RandomNumberGenerator};
+
+    use super::{GenerationModel, Replacement};
+
+    #[test]
+    fn test_select_loser() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let scores = [5.0, 4.0, 3.0, 2.0];
+        assert_eq!(Replacement::Worst.select_loser(&scores, &mut rng), 3);
+        for _ in 0..100 {
+            let loser = Replacement::Tournament(2).select_loser(&scores, &mut rng);
+            assert!((1..4).contains(&loser));
+        }
+        assert_eq!(Replacement::Worst.select_loser(&[1.0], &mut rng), 0);
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        for generation_model in [
+            GenerationModel::Generational,
+            GenerationModel::SteadyState {
+                num_offspring: 2,
+                replacement: Replacement::Worst,
+            },
+            GenerationModel::SteadyState {
+                num_offspring: 1,
+                replacement: Replacement::Tournament(3),
+            },
+        ] {
+            let line = generation_model.to_checkpoint();
+            assert_eq!(
+                GenerationModel::from_checkpoint(&line),
+                Some(generation_model)
+            );
+        }
+    }
+}
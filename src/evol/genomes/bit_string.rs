@@ -0,0 +1,122 @@
+use crate::evol::{
+    evol_coordinator::EvolutionCoordinator,
+    fitness_cache::fingerprint_of,
+    operators::crossover_rng,
+    rand::RandomNumberGenerator,
+    traits::{Fingerprint, InitialisablePhenotype, Phenotype, PhenotypeDistance},
+};
+
+// a fixed-length string of bits with uniform crossover and bit-flip mutation
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitString {
+    bits: Vec<bool>,
+    mutation_rate: f64, // the probability of every bit to flip, 1 / length by default
+}
+
+impl BitString {
+    pub fn new(bits: Vec<bool>) -> BitString {
+        let mutation_rate = 1.0 / bits.len().max(1) as f64;
+        BitString {
+            bits,
+            mutation_rate,
+        }
+    }
+
+    pub fn random(len: usize, rng: &mut RandomNumberGenerator) -> BitString {
+        BitString::new((0..len).map(|_| rng.next_u32() & 1 == 1).collect())
+    }
+
+    pub fn with_mutation_rate(mut self, mutation_rate: f64) -> BitString {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+
+    pub fn get_bits(&self) -> &[bool] {
+        &self.bits
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.bits.iter().filter(|&&bit| bit).count()
+    }
+}
+
+impl Phenotype for BitString {
+    fn crossover(&mut self, other: &Self) {
+        let mut rng = crossover_rng(&self.bits, &other.bits);
+        for (bit, &other_bit) in self.bits.iter_mut().zip(other.bits.iter()) {
+            if rng.next_u32() & 1 == 1 {
+                *bit = other_bit;
+            }
+        }
+    }
+
+    fn mutate(&mut self, rng: &mut RandomNumberGenerator, _: EvolutionCoordinator) {
+        for bit in self.bits.iter_mut() {
+            if rng.fetch_unit() < self.mutation_rate {
+                *bit = !*bit;
+            }
+        }
+    }
+
+    fn to_string_internal(&self) -> String {
+        self.bits
+            .iter()
+            .map(|&bit| if bit { '1' } else { '0' })
+            .collect()
+    }
+}
+
+impl InitialisablePhenotype for BitString {
+    fn sample_uniform(&self, rng: &mut RandomNumberGenerator) -> Self {
+        let mut sample = self.clone();
+        sample.bits = BitString::random(self.bits.len(), rng).bits;
+        sample
+    }
+}
+
+impl Fingerprint for BitString {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(&self.bits)
+    }
+}
+
+// the Hamming distance
+impl PhenotypeDistance for BitString {
+    fn distance(&self, other: &Self) -> f64 {
+        self.bits
+            .iter()
+            .zip(other.bits.iter())
+            .filter(|(a, b)| a != b)
+            .count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        evol_launcher::EvolutionLauncher, evol_options::EvolutionOptions,
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy, rand::RandomNumberGenerator,
+        traits::Phenotype,
+    };
+
+    use super::BitString;
+
+    #[test]
+    fn test_one_max() {
+        let launcher: EvolutionLauncher<BitString, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(|phenotype: &BitString| phenotype.count_ones() as f64),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = launcher
+            .evolve(
+                EvolutionOptions::new(),
+                BitString::new(vec![false; 32]),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(result.winner.count_ones(), 32);
+        assert_eq!(result.winner.to_string_internal(), "1".repeat(32));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::evol::{
+    evol_coordinator::EvolutionCoordinator,
+    fitness_cache::fingerprint_of,
+    operators::{crossover_rng, real::latin_hypercube},
+    rand::RandomNumberGenerator,
+    traits::{Fingerprint, InitialisablePhenotype, Phenotype, PhenotypeDistance},
+};
+
+// a vector of integers, each within its own bounds, with uniform crossover and random reset mutation
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegerVector {
+    values: Vec<i64>,
+    bounds: Vec<(i64, i64)>, // the inclusive lower and upper bound of every value
+    mutation_rate: f64,      // the probability of every value to be reset, 1 / length by default
+}
+
+impl IntegerVector {
+    // panics unless there is one bound for every value and every lower bound is at most its upper bound
+    pub fn new(values: Vec<i64>, bounds: Vec<(i64, i64)>) -> IntegerVector {
+        assert_eq!(values.len(), bounds.len(), "one bound is needed per value");
+        assert!(
+            bounds.iter().all(|(lower, upper)| lower <= upper),
+            "the lower bounds must not exceed the upper bounds"
+        );
+        let mutation_rate = 1.0 / values.len().max(1) as f64;
+        let values = values
+            .iter()
+            .zip(bounds.iter())
+            .map(|(value, (lower, upper))| *value.clamp(lower, upper))
+            .collect();
+        IntegerVector {
+            values,
+            bounds,
+            mutation_rate,
+        }
+    }
+
+    pub fn random(bounds: Vec<(i64, i64)>, rng: &mut RandomNumberGenerator) -> IntegerVector {
+        let values = bounds
+            .iter()
+            .map(|&(lower, upper)| random_in(lower, upper, rng))
+            .collect();
+        IntegerVector::new(values, bounds)
+    }
+
+    pub fn with_mutation_rate(mut self, mutation_rate: f64) -> IntegerVector {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+
+    pub fn get_values(&self) -> &[i64] {
+        &self.values
+    }
+
+    pub fn get_bounds(&self) -> &[(i64, i64)] {
+        &self.bounds
+    }
+}
+
+impl Phenotype for IntegerVector {
+    fn crossover(&mut self, other: &Self) {
+        let mut rng = crossover_rng(&self.values, &other.values);
+        for (value, &other_value) in self.values.iter_mut().zip(other.values.iter()) {
+            if rng.next_u32() & 1 == 1 {
+                *value = other_value;
+            }
+        }
+    }
+
+    fn mutate(&mut self, rng: &mut RandomNumberGenerator, _: EvolutionCoordinator) {
+        for (value, &(lower, upper)) in self.values.iter_mut().zip(self.bounds.iter()) {
+            if rng.fetch_unit() < self.mutation_rate {
+                *value = random_in(lower, upper, rng);
+            }
+        }
+    }
+
+    fn to_string_internal(&self) -> String {
+        format!("{:?}", self.values)
+    }
+}
+
+// uniformly distributed in lower..=upper, the width is computed in 128 bits so that it can not overflow
+fn random_in(lower: i64, upper: i64, rng: &mut RandomNumberGenerator) -> i64 {
+    let width = upper as i128 - lower as i128 + 1;
+    let offset = ((rng.fetch_unit() * width as f64) as i128).min(width - 1);
+    (lower as i128 + offset) as i64
+}
+
+impl InitialisablePhenotype for IntegerVector {
+    fn sample_uniform(&self, rng: &mut RandomNumberGenerator) -> Self {
+        let mut sample = self.clone();
+        sample.values = IntegerVector::random(self.bounds.clone(), rng).values;
+        sample
+    }
+
+    // the strata of lower..upper + 1 are rounded down, so every integer is drawn about equally often
+    fn sample_latin_hypercube(
+        &self,
+        num_samples: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<Self> {
+        let widened: Vec<(f64, f64)> = self
+            .bounds
+            .iter()
+            .map(|&(lower, upper)| (lower as f64, upper as f64 + 1.0))
+            .collect();
+        latin_hypercube(&widened, num_samples, rng)
+            .into_iter()
+            .map(|values| {
+                let mut sample = self.clone();
+                sample.values = values
+                    .iter()
+                    .zip(self.bounds.iter())
+                    .map(|(value, &(lower, upper))| (value.floor() as i64).clamp(lower, upper))
+                    .collect();
+                sample
+            })
+            .collect()
+    }
+}
+
+impl Fingerprint for IntegerVector {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(&self.values)
+    }
+}
+
+// the Manhattan distance
+impl PhenotypeDistance for IntegerVector {
+    fn distance(&self, other: &Self) -> f64 {
+        self.values
+            .iter()
+            .zip(other.values.iter())
+            .map(|(a, b)| a.abs_diff(*b) as f64)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        evol_launcher::EvolutionLauncher, evol_options::EvolutionOptions,
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy, rand::RandomNumberGenerator,
+    };
+
+    use super::{random_in, IntegerVector};
+
+    #[test]
+    fn test_targets() {
+        let targets = [3, -2, 7, 0];
+        let launcher: EvolutionLauncher<
+            IntegerVector,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(move |phenotype: &IntegerVector| {
+                -phenotype
+                    .get_values()
+                    .iter()
+                    .zip(targets.iter())
+                    .map(|(value, target)| (value - target).abs() as f64)
+                    .sum::<f64>()
+            }),
+        );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let starting_value = IntegerVector::random(vec![(-10, 10); 4], &mut rng);
+        let winner = launcher
+            .evolve(EvolutionOptions::new(), starting_value, &mut rng)
+            .unwrap()
+            .winner;
+        assert_eq!(winner.get_values(), &targets);
+    }
+
+    #[test]
+    fn test_extreme_bounds() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        for _ in 0..100 {
+            let value = random_in(i64::MIN, i64::MAX, &mut rng);
+            assert!((i64::MIN..=i64::MAX).contains(&value));
+            assert_eq!(random_in(i64::MAX, i64::MAX, &mut rng), i64::MAX);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one bound is needed per value")]
+    fn test_missing_bound() {
+        IntegerVector::new(vec![1, 2], vec![(0, 3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the lower bounds must not exceed the upper bounds")]
+    fn test_inverted_bounds() {
+        IntegerVector::new(vec![1], vec![(3, 0)]);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod bit_string;
+pub mod integer_vector;
+pub mod permutation;
+pub mod real_vector;
+pub mod self_adaptive_real_vector;
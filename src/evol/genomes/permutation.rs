@@ -0,0 +1,242 @@
+use crate::evol::{
+    evol_coordinator::EvolutionCoordinator,
+    fitness_cache::fingerprint_of,
+    operators::{crossover_rng, permutation},
+    rand::RandomNumberGenerator,
+    traits::{Fingerprint, InitialisablePhenotype, Phenotype, PhenotypeDistance},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PermutationCrossover {
+    Order,
+    PartiallyMapped,
+    Cycle,
+    EdgeRecombination,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PermutationMutation {
+    Swap,
+    Insert,
+    Inversion,
+    Scramble,
+}
+
+// an ordering of 0..len, order crossover and swap mutation by default, every operator keeps it valid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permutation {
+    order: Vec<usize>,
+    crossover: PermutationCrossover,
+    mutation: PermutationMutation,
+}
+
+impl Permutation {
+    // panics unless order is a permutation of 0..len
+    pub fn new(order: Vec<usize>) -> Permutation {
+        assert!(
+            is_permutation(&order),
+            "the order must hold every element of 0..len once"
+        );
+        Permutation {
+            order,
+            crossover: PermutationCrossover::Order,
+            mutation: PermutationMutation::Swap,
+        }
+    }
+
+    pub fn identity(len: usize) -> Permutation {
+        Permutation::new((0..len).collect())
+    }
+
+    // Fisher-Yates shuffle
+    pub fn random(len: usize, rng: &mut RandomNumberGenerator) -> Permutation {
+        let mut order: Vec<usize> = (0..len).collect();
+        for i in (1..len).rev() {
+            order.swap(i, rng.fetch_index(i + 1));
+        }
+        Permutation::new(order)
+    }
+
+    pub fn with_crossover(mut self, crossover: PermutationCrossover) -> Permutation {
+        self.crossover = crossover;
+        self
+    }
+
+    pub fn with_mutation(mut self, mutation: PermutationMutation) -> Permutation {
+        self.mutation = mutation;
+        self
+    }
+
+    pub fn get_order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn is_valid(&self) -> bool {
+        is_permutation(&self.order)
+    }
+}
+
+fn is_permutation(order: &[usize]) -> bool {
+    let mut seen = vec![false; order.len()];
+    for &element in order.iter() {
+        if element >= seen.len() || seen[element] {
+            return false;
+        }
+        seen[element] = true;
+    }
+    true
+}
+
+impl Phenotype for Permutation {
+    fn crossover(&mut self, other: &Self) {
+        let mut rng = crossover_rng(&self.order, &other.order);
+        self.order = match self.crossover {
+            PermutationCrossover::Order => {
+                permutation::order_crossover(&self.order, &other.order, &mut rng)
+            }
+            PermutationCrossover::PartiallyMapped => {
+                permutation::partially_mapped_crossover(&self.order, &other.order, &mut rng)
+            }
+            PermutationCrossover::Cycle => permutation::cycle_crossover(&self.order, &other.order),
+            PermutationCrossover::EdgeRecombination => {
+                permutation::edge_recombination(&self.order, &other.order, &mut rng)
+            }
+        };
+    }
+
+    fn mutate(&mut self, rng: &mut RandomNumberGenerator, _: EvolutionCoordinator) {
+        match self.mutation {
+            PermutationMutation::Swap => permutation::swap_mutation(&mut self.order, rng),
+            PermutationMutation::Insert => permutation::insert_mutation(&mut self.order, rng),
+            PermutationMutation::Inversion => permutation::inversion_mutation(&mut self.order, rng),
+            PermutationMutation::Scramble => permutation::scramble_mutation(&mut self.order, rng),
+        }
+    }
+
+    fn to_string_internal(&self) -> String {
+        format!("{:?}", self.order)
+    }
+}
+
+impl InitialisablePhenotype for Permutation {
+    fn sample_uniform(&self, rng: &mut RandomNumberGenerator) -> Self {
+        let mut sample = self.clone();
+        sample.order = Permutation::random(self.order.len(), rng).order;
+        sample
+    }
+}
+
+impl Fingerprint for Permutation {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(&self.order)
+    }
+}
+
+// the number of positions holding different elements
+impl PhenotypeDistance for Permutation {
+    fn distance(&self, other: &Self) -> f64 {
+        self.order
+            .iter()
+            .zip(other.order.iter())
+            .filter(|(a, b)| a != b)
+            .count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::evol::{
+        evol_coordinator::EvolutionCoordinator, evol_launcher::EvolutionLauncher,
+        evol_options::EvolutionOptions, ordinary_evol_strategy::OrdinaryEvolutionStrategy,
+        rand::RandomNumberGenerator, test_evol::TravellingSalesmanChallenge, traits::Phenotype,
+    };
+
+    use super::{Permutation, PermutationCrossover, PermutationMutation};
+
+    #[test]
+    fn test_stays_valid() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_coordinator = EvolutionCoordinator::new(&EvolutionOptions::new());
+        for _ in 0..100 {
+            let mut first = Permutation::random(10, &mut rng);
+            let second = Permutation::random(10, &mut rng);
+            first.crossover(&second);
+            assert!(first.is_valid());
+            first.mutate(&mut rng, evol_coordinator);
+            assert!(first.is_valid());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the order must hold every element of 0..len once")]
+    fn test_invalid_order() {
+        Permutation::new(vec![0, 2, 2]);
+    }
+
+    // the order crossover of first and second that keeps first[start..end]
+    fn order_crossover(first: &[usize], second: &[usize], start: usize, end: usize) -> Vec<usize> {
+        let len = first.len();
+        let kept = &first[start..end];
+        let mut fill = (end..end + len)
+            .map(|i| second[i % len])
+            .filter(|element| !kept.contains(element));
+        let mut child = first.to_vec();
+        for i in end..end + len - kept.len() {
+            child[i % len] = fill.next().unwrap();
+        }
+        child
+    }
+
+    #[test]
+    fn test_crossover_keeps_a_segment() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        for _ in 0..20 {
+            let first = Permutation::random(8, &mut rng);
+            let second = Permutation::random(8, &mut rng);
+            let mut child = first.clone();
+            child.crossover(&second);
+            // the child keeps a non-empty segment of first in place and has the rest in the order of second
+            let num_segments = (0..8)
+                .flat_map(|start| (start + 1..=8).map(move |end| (start, end)))
+                .filter(|&(start, end)| {
+                    child.get_order()
+                        == order_crossover(first.get_order(), second.get_order(), start, end)
+                })
+                .count();
+            assert!(num_segments >= 1);
+        }
+    }
+
+    #[test]
+    fn test_travelling_salesman() {
+        // the shortest tour around cities on a circle visits them in angular order
+        let num_cities = 12;
+        let cities: Vec<(f64, f64)> = (0..num_cities)
+            .map(|i| {
+                let angle = (i * 5 % num_cities) as f64 * 2.0 * PI / num_cities as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        let challenge = TravellingSalesmanChallenge::new(cities);
+        let shortest_length = 2.0 * num_cities as f64 * (PI / num_cities as f64).sin();
+        let launcher: EvolutionLauncher<Permutation, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(move |phenotype: &Permutation| challenge.score(phenotype)),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let starting_value = Permutation::identity(num_cities)
+            .with_crossover(PermutationCrossover::EdgeRecombination)
+            .with_mutation(PermutationMutation::Inversion);
+        let result = launcher
+            .evolve(
+                EvolutionOptions::new().with_num_generations(200),
+                starting_value,
+                &mut rng,
+            )
+            .unwrap();
+        assert!((-result.score - shortest_length).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::evol::{
+    evol_coordinator::EvolutionCoordinator,
+    fitness_cache::fingerprint_of,
+    mutation_schedule::MutationSchedule,
+    operators::{
+        crossover_rng,
+        real::{
+            apply_bounds, arithmetic_crossover, gaussian_mutation, latin_hypercube, BoundsHandling,
+        },
+    },
+    rand::RandomNumberGenerator,
+    traits::{
+        Fingerprint, InitialisablePhenotype, Phenotype, PhenotypeDistance, RealVectorPhenotype,
+    },
+};
+
+// a vector of reals, each within its own bounds, with whole arithmetic crossover and Gaussian mutation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealVector {
+    values: Vec<f64>,
+    bounds: Vec<(f64, f64)>, // the inclusive lower and upper bound of every value
+    schedule: MutationSchedule, // the mutation's standard deviation relative to the bounds' width
+    bounds_handling: BoundsHandling,
+}
+
+impl RealVector {
+    // panics unless there is one bound for every value and every lower bound is at most its upper bound
+    pub fn new(values: Vec<f64>, bounds: Vec<(f64, f64)>) -> RealVector {
+        assert_eq!(values.len(), bounds.len(), "one bound is needed per value");
+        assert!(
+            bounds.iter().all(|(lower, upper)| lower <= upper),
+            "the lower bounds must not exceed the upper bounds"
+        );
+        let mut real_vector = RealVector {
+            values: Vec::new(),
+            bounds,
+            schedule: MutationSchedule::Exponential {
+                start: 0.1,
+                end: 0.001,
+            },
+            bounds_handling: BoundsHandling::Clip,
+        };
+        real_vector.set_values(&values);
+        real_vector
+    }
+
+    pub fn random(bounds: Vec<(f64, f64)>, rng: &mut RandomNumberGenerator) -> RealVector {
+        let values = bounds
+            .iter()
+            .map(|(lower, upper)| lower + (upper - lower) * rng.fetch_unit())
+            .collect();
+        RealVector::new(values, bounds)
+    }
+
+    pub fn with_schedule(mut self, schedule: MutationSchedule) -> RealVector {
+        self.schedule = schedule;
+        self
+    }
+
+    pub fn with_bounds_handling(mut self, bounds_handling: BoundsHandling) -> RealVector {
+        self.bounds_handling = bounds_handling;
+        self
+    }
+
+    pub fn get_bounds(&self) -> &[(f64, f64)] {
+        &self.bounds
+    }
+}
+
+impl Phenotype for RealVector {
+    fn crossover(&mut self, other: &Self) {
+        let bits: Vec<u64> = self.values.iter().map(|value| value.to_bits()).collect();
+        let other_bits: Vec<u64> = other.values.iter().map(|value| value.to_bits()).collect();
+        let weight = crossover_rng(&bits, &other_bits).fetch_unit();
+        self.values = arithmetic_crossover(&self.values, &other.values, weight);
+    }
+
+    fn mutate(&mut self, rng: &mut RandomNumberGenerator, evol_coordinator: EvolutionCoordinator) {
+        let step_size = self.schedule.step_size(&evol_coordinator);
+        // the deviation is relative, so the noise is drawn for the unit interval and scaled per value
+        let mut noise = vec![0.0; self.values.len()];
+        gaussian_mutation(&mut noise, step_size, rng);
+        for ((value, (lower, upper)), noise) in
+            self.values.iter_mut().zip(self.bounds.iter()).zip(noise)
+        {
+            *value += noise * (upper - lower);
+        }
+        apply_bounds(&mut self.values, &self.bounds, self.bounds_handling);
+    }
+
+    fn to_string_internal(&self) -> String {
+        format!("{:?}", self.values)
+    }
+}
+
+impl RealVectorPhenotype for RealVector {
+    fn get_values(&self) -> Vec<f64> {
+        self.values.clone()
+    }
+
+    // values outside the bounds are brought back with the bounds handling
+    fn set_values(&mut self, values: &[f64]) {
+        self.values = values.to_vec();
+        apply_bounds(&mut self.values, &self.bounds, self.bounds_handling);
+    }
+}
+
+impl InitialisablePhenotype for RealVector {
+    fn sample_uniform(&self, rng: &mut RandomNumberGenerator) -> Self {
+        let mut sample = self.clone();
+        sample.values = RealVector::random(self.bounds.clone(), rng).values;
+        sample
+    }
+
+    fn sample_latin_hypercube(
+        &self,
+        num_samples: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<Self> {
+        latin_hypercube(&self.bounds, num_samples, rng)
+            .into_iter()
+            .map(|values| {
+                let mut sample = self.clone();
+                sample.set_values(&values);
+                sample
+            })
+            .collect()
+    }
+}
+
+impl Fingerprint for RealVector {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(
+            &self
+                .values
+                .iter()
+                .map(|value| value.to_bits())
+                .collect::<Vec<u64>>(),
+        )
+    }
+}
+
+// the Euclidean distance
+impl PhenotypeDistance for RealVector {
+    fn distance(&self, other: &Self) -> f64 {
+        self.values
+            .iter()
+            .zip(other.values.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        evol_launcher::EvolutionLauncher, evol_options::EvolutionOptions,
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy, rand::RandomNumberGenerator,
+        traits::RealVectorPhenotype,
+    };
+
+    use super::RealVector;
+
+    #[test]
+    fn test_sphere() {
+        let launcher: EvolutionLauncher<RealVector, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(
+                OrdinaryEvolutionStrategy,
+                Box::new(|phenotype: &RealVector| {
+                    -phenotype
+                        .get_values()
+                        .iter()
+                        .map(|value| (value - 0.3) * (value - 0.3))
+                        .sum::<f64>()
+                }),
+            );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let starting_value = RealVector::random(vec![(-1.0, 1.0); 3], &mut rng);
+        let winner = launcher
+            .evolve(EvolutionOptions::new(), starting_value, &mut rng)
+            .unwrap()
+            .winner;
+        for value in winner.get_values() {
+            assert!((value - 0.3).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let real_vector = RealVector::new(vec![-5.0, 0.5, 5.0], vec![(0.0, 1.0); 3]);
+        assert_eq!(real_vector.get_values(), vec![0.0, 0.5, 1.0]);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::evol::{
+    evol_coordinator::EvolutionCoordinator,
+    fitness_cache::fingerprint_of,
+    operators::real::{
+        apply_bounds, arithmetic_crossover, latin_hypercube, self_adaptive_mutation, BoundsHandling,
+    },
+    rand::RandomNumberGenerator,
+    traits::{
+        Fingerprint, InitialisablePhenotype, Phenotype, PhenotypeDistance, RealVectorPhenotype,
+    },
+};
+
+// a vector of reals that carries one mutation step size per value, the step sizes mutate along with the
+// values so that selection tunes them instead of a schedule; recombination is intermediate, it averages
+// the values and takes the geometric mean of the step sizes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfAdaptiveRealVector {
+    values: Vec<f64>,
+    step_sizes: Vec<f64>, // the standard deviation of every value's mutation relative to its bounds' width
+    bounds: Vec<(f64, f64)>, // the inclusive lower and upper bound of every value
+    min_step_size: f64,
+    bounds_handling: BoundsHandling,
+}
+
+impl SelfAdaptiveRealVector {
+    // panics unless there is one bound for every value and every lower bound is at most its upper bound
+    pub fn new(values: Vec<f64>, bounds: Vec<(f64, f64)>) -> SelfAdaptiveRealVector {
+        assert_eq!(values.len(), bounds.len(), "one bound is needed per value");
+        assert!(
+            bounds.iter().all(|(lower, upper)| lower <= upper),
+            "the lower bounds must not exceed the upper bounds"
+        );
+        let mut real_vector = SelfAdaptiveRealVector {
+            values: Vec::new(),
+            step_sizes: vec![0.1; bounds.len()],
+            bounds,
+            min_step_size: 1e-9,
+            bounds_handling: BoundsHandling::Clip,
+        };
+        real_vector.set_values(&values);
+        real_vector
+    }
+
+    pub fn random(
+        bounds: Vec<(f64, f64)>,
+        rng: &mut RandomNumberGenerator,
+    ) -> SelfAdaptiveRealVector {
+        let values = bounds
+            .iter()
+            .map(|(lower, upper)| lower + (upper - lower) * rng.fetch_unit())
+            .collect();
+        SelfAdaptiveRealVector::new(values, bounds)
+    }
+
+    // the same initial step size for every value
+    pub fn with_step_size(mut self, step_size: f64) -> SelfAdaptiveRealVector {
+        self.step_sizes = vec![step_size.max(self.min_step_size); self.values.len()];
+        self
+    }
+
+    pub fn with_min_step_size(mut self, min_step_size: f64) -> SelfAdaptiveRealVector {
+        self.min_step_size = min_step_size;
+        self
+    }
+
+    pub fn with_bounds_handling(
+        mut self,
+        bounds_handling: BoundsHandling,
+    ) -> SelfAdaptiveRealVector {
+        self.bounds_handling = bounds_handling;
+        self
+    }
+
+    pub fn get_step_sizes(&self) -> &[f64] {
+        &self.step_sizes
+    }
+
+    pub fn get_bounds(&self) -> &[(f64, f64)] {
+        &self.bounds
+    }
+}
+
+impl Phenotype for SelfAdaptiveRealVector {
+    fn crossover(&mut self, other: &Self) {
+        self.values = arithmetic_crossover(&self.values, &other.values, 0.5);
+        for (step_size, other) in self.step_sizes.iter_mut().zip(other.step_sizes.iter()) {
+            *step_size = (*step_size * other).sqrt();
+        }
+    }
+
+    fn mutate(&mut self, rng: &mut RandomNumberGenerator, _evol_coordinator: EvolutionCoordinator) {
+        // the step sizes are relative, so the noise is drawn for the unit interval and scaled per value
+        let mut noise = vec![0.0; self.values.len()];
+        self_adaptive_mutation(&mut noise, &mut self.step_sizes, self.min_step_size, rng);
+        for ((value, (lower, upper)), noise) in
+            self.values.iter_mut().zip(self.bounds.iter()).zip(noise)
+        {
+            *value += noise * (upper - lower);
+        }
+        apply_bounds(&mut self.values, &self.bounds, self.bounds_handling);
+    }
+
+    fn to_string_internal(&self) -> String {
+        format!("{:?}", self.values)
+    }
+}
+
+impl RealVectorPhenotype for SelfAdaptiveRealVector {
+    fn get_values(&self) -> Vec<f64> {
+        self.values.clone()
+    }
+
+    // values outside the bounds are brought back with the bounds handling
+    fn set_values(&mut self, values: &[f64]) {
+        self.values = values.to_vec();
+        apply_bounds(&mut self.values, &self.bounds, self.bounds_handling);
+    }
+}
+
+// the samples start with the step sizes of the template
+impl InitialisablePhenotype for SelfAdaptiveRealVector {
+    fn sample_uniform(&self, rng: &mut RandomNumberGenerator) -> Self {
+        let mut sample = self.clone();
+        sample.values = SelfAdaptiveRealVector::random(self.bounds.clone(), rng).values;
+        sample
+    }
+
+    fn sample_latin_hypercube(
+        &self,
+        num_samples: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<Self> {
+        latin_hypercube(&self.bounds, num_samples, rng)
+            .into_iter()
+            .map(|values| {
+                let mut sample = self.clone();
+                sample.set_values(&values);
+                sample
+            })
+            .collect()
+    }
+}
+
+// the step sizes do not change the score, so they are not part of the fingerprint
+impl Fingerprint for SelfAdaptiveRealVector {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(
+            &self
+                .values
+                .iter()
+                .map(|value| value.to_bits())
+                .collect::<Vec<u64>>(),
+        )
+    }
+}
+
+// the Euclidean distance
+impl PhenotypeDistance for SelfAdaptiveRealVector {
+    fn distance(&self, other: &Self) -> f64 {
+        self.values
+            .iter()
+            .zip(other.values.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::traits::{Phenotype, RealVectorPhenotype};
+
+    use super::SelfAdaptiveRealVector;
+
+    #[test]
+    fn test_crossover() {
+        let mut first =
+            SelfAdaptiveRealVector::new(vec![0.0, 1.0], vec![(0.0, 1.0); 2]).with_step_size(0.4);
+        let second =
+            SelfAdaptiveRealVector::new(vec![1.0, 1.0], vec![(0.0, 1.0); 2]).with_step_size(0.1);
+        first.crossover(&second);
+        assert_eq!(first.get_values(), vec![0.5, 1.0]);
+        for step_size in first.get_step_sizes() {
+            assert!((step_size - 0.2).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use super::{
+    evol_launcher::Fitness,
+    traits::{Fingerprint, Phenotype},
+};
+
+#[derive(Clone)]
+pub struct HallOfFameEntry<Pheno: Phenotype> {
+    pub phenotype: Pheno,
+    pub score: f64,
+    pub generation: usize, // the generation the phenotype was first scored in
+}
+
+// the best distinct phenotypes ever scored in a run, phenotypes are told apart by their fingerprint
+#[derive(Clone)]
+pub struct HallOfFame<Pheno: Phenotype> {
+    size: usize,
+    fingerprint_fn: fn(&Pheno) -> u64,
+    entries: Vec<HallOfFameEntry<Pheno>>, // the best entry comes first
+    fingerprints: HashSet<u64>,           // the fingerprint of every entry
+}
+
+impl<Pheno: Phenotype + Fingerprint> HallOfFame<Pheno> {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            fingerprint_fn: Pheno::fingerprint,
+            entries: Vec::new(),
+            fingerprints: HashSet::new(),
+        }
+    }
+
+    pub fn from_entries(size: usize, entries: Vec<HallOfFameEntry<Pheno>>) -> Self {
+        let mut hall_of_fame = Self::new(size);
+        for entry in entries {
+            hall_of_fame.insert(entry);
+        }
+        hall_of_fame
+    }
+}
+
+impl<Pheno: Phenotype> HallOfFame<Pheno> {
+    // fitness must be sorted, the best phenotype first
+    pub fn record(&mut self, fitness: &[Fitness<Pheno>], generation: usize) {
+        for fit in fitness.iter() {
+            if !self.admits(fit.score) {
+                break;
+            }
+            self.insert(HallOfFameEntry {
+                phenotype: fit.phenotype.clone(),
+                score: fit.score,
+                generation,
+            });
+        }
+    }
+
+    pub fn get_entries(&self) -> &[HallOfFameEntry<Pheno>] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<HallOfFameEntry<Pheno>> {
+        self.entries
+    }
+
+    fn admits(&self, score: f64) -> bool {
+        self.entries.len() < self.size
+            || self
+                .entries
+                .last()
+                .is_some_and(|worst| score.total_cmp(&worst.score).is_gt())
+    }
+
+    // a phenotype already in the hall of fame keeps its generation of discovery, among equal scores
+    // the earlier entry ranks first
+    fn insert(&mut self, entry: HallOfFameEntry<Pheno>) {
+        if !self.admits(entry.score)
+            || !self
+                .fingerprints
+                .insert((self.fingerprint_fn)(&entry.phenotype))
+        {
+            return;
+        }
+        let position = self
+            .entries
+            .partition_point(|other| other.score.total_cmp(&entry.score).is_ge());
+        self.entries.insert(position, entry);
+        if self.entries.len() > self.size {
+            let evicted = self.entries.pop().expect("the hall of fame is not empty");
+            self.fingerprints
+                .remove(&(self.fingerprint_fn)(&evicted.phenotype));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{evol_launcher::Fitness, test_evol::XCoordinate};
+
+    use super::HallOfFame;
+
+    fn fitness(xs: &[f64]) -> Vec<Fitness<XCoordinate>> {
+        xs.iter()
+            .map(|&x| Fitness::new(XCoordinate::new(x), x))
+            .collect()
+    }
+
+    #[test]
+    fn test_record() {
+        let mut hall_of_fame = HallOfFame::new(3);
+        hall_of_fame.record(&fitness(&[5.0, 5.0, 2.0, 1.0]), 1);
+        hall_of_fame.record(&fitness(&[5.0, 4.0, 3.0, 2.0]), 2);
+        let entries: Vec<(f64, usize)> = hall_of_fame
+            .get_entries()
+            .iter()
+            .map(|entry| (entry.score, entry.generation))
+            .collect();
+        // the duplicate 5 is kept once with the generation it was found in first
+        assert_eq!(entries, vec![(5.0, 1), (4.0, 2), (3.0, 2)]);
+        let resized = HallOfFame::from_entries(2, hall_of_fame.into_entries());
+        assert_eq!(resized.get_entries().len(), 2);
+        assert_eq!(resized.get_entries()[1].score, 4.0);
+    }
+}
@@ -0,0 +1,187 @@
+use super::{
+    rand::RandomNumberGenerator,
+    traits::{InitialisablePhenotype, Phenotype},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    Uniform,        // every member independently
+    LatinHypercube, // the members together cover every dimension evenly
+}
+
+// how the initial population of a run is filled: the seeds come first, the members missing to
+// population_size are sampled from the search space of a template phenotype
+#[derive(Clone)]
+pub struct Initialisation<Pheno: Phenotype> {
+    population_size: usize,
+    sampling: Sampling,
+    seeds: Vec<Pheno>, // only the first population_size seeds are used
+}
+
+impl<Pheno: Phenotype> Initialisation<Pheno> {
+    pub fn new(population_size: usize, sampling: Sampling) -> Self {
+        Self {
+            population_size,
+            sampling,
+            seeds: Vec::new(),
+        }
+    }
+
+    pub fn with_seeds(mut self, seeds: Vec<Pheno>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    pub fn get_population_size(&self) -> usize {
+        self.population_size
+    }
+
+    pub fn get_sampling(&self) -> Sampling {
+        self.sampling
+    }
+
+    pub fn get_seeds(&self) -> &[Pheno] {
+        &self.seeds
+    }
+
+    pub fn populate(&self, template: &Pheno, rng: &mut RandomNumberGenerator) -> Vec<Pheno>
+    where
+        Pheno: InitialisablePhenotype,
+    {
+        let mut population: Vec<Pheno> = self
+            .seeds
+            .iter()
+            .take(self.population_size)
+            .cloned()
+            .collect();
+        let num_samples = self.population_size - population.len();
+        match self.sampling {
+            Sampling::Uniform => {
+                population.extend((0..num_samples).map(|_| template.sample_uniform(rng)))
+            }
+            Sampling::LatinHypercube => {
+                population.extend(template.sample_latin_hypercube(num_samples, rng))
+            }
+        }
+        population
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::evol::{
+        evol_launcher::EvolutionLauncher,
+        evol_options::EvolutionOptions,
+        genomes::real_vector::RealVector,
+        observer::{EvolutionObserver, GenerationReport, ObserverAction},
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy,
+        rand::RandomNumberGenerator,
+        termination::StopReason,
+        traits::RealVectorPhenotype,
+    };
+
+    use super::{Initialisation, Sampling};
+
+    struct EventObserver {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl EvolutionObserver<RealVector> for EventObserver {
+        fn on_start(&mut self) {
+            self.events.lock().unwrap().push("start".to_string());
+        }
+
+        fn on_generation(&mut self, report: &GenerationReport<RealVector>) -> ObserverAction {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("generation {}", report.generation));
+            ObserverAction::Stop
+        }
+    }
+
+    #[test]
+    fn test_populate() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let template = RealVector::new(vec![0.0; 2], vec![(0.0, 1.0), (5.0, 6.0)]);
+        let seed = RealVector::new(vec![0.5, 5.5], template.get_bounds().to_vec());
+        for sampling in [Sampling::Uniform, Sampling::LatinHypercube] {
+            let population = Initialisation::new(10, sampling)
+                .with_seeds(vec![seed.clone()])
+                .populate(&template, &mut rng);
+            assert_eq!(population.len(), 10);
+            assert_eq!(population[0], seed);
+            for member in population.iter() {
+                let values = member.get_values();
+                assert!((0.0..=1.0).contains(&values[0]));
+                assert!((5.0..=6.0).contains(&values[1]));
+            }
+        }
+        let seeds_only = Initialisation::new(1, Sampling::Uniform)
+            .with_seeds(vec![seed.clone(), template.clone()])
+            .populate(&template, &mut rng);
+        assert_eq!(seeds_only, vec![seed]);
+    }
+
+    #[test]
+    fn test_multimodal() {
+        // a narrow high peak at 0.9 and a broad low one at 0.2, the starting value sits on the low one
+        let score = |phenotype: &RealVector| {
+            let x = phenotype.get_values()[0];
+            (2.0 - 400.0 * (x - 0.9) * (x - 0.9)).max(1.0 - 4.0 * (x - 0.2) * (x - 0.2))
+        };
+        let launcher: EvolutionLauncher<RealVector, EvolutionOptions, OrdinaryEvolutionStrategy> =
+            EvolutionLauncher::new(OrdinaryEvolutionStrategy, Box::new(score));
+        let template = RealVector::new(vec![0.2], vec![(0.0, 1.0)]);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let single_start = launcher
+            .evolve(EvolutionOptions::new(), template.clone(), &mut rng)
+            .unwrap();
+        assert!((single_start.winner.get_values()[0] - 0.2).abs() < 1e-2);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = launcher
+            .evolve_initialised(
+                EvolutionOptions::new(),
+                &template,
+                &Initialisation::new(50, Sampling::LatinHypercube),
+                &mut rng,
+            )
+            .unwrap();
+        assert!((result.winner.get_values()[0] - 0.9).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_initial_population_is_reported() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut launcher: EvolutionLauncher<
+            RealVector,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(|phenotype: &RealVector| phenotype.get_values()[0]),
+        );
+        launcher.add_observer(Box::new(EventObserver {
+            events: events.clone(),
+        }));
+        let template = RealVector::new(vec![0.0], vec![(0.0, 1.0)]);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = launcher
+            .evolve_initialised(
+                EvolutionOptions::new(),
+                &template,
+                &Initialisation::new(10, Sampling::Uniform),
+                &mut rng,
+            )
+            .unwrap();
+        // the observer stops the run at the initial population
+        assert_eq!(*events.lock().unwrap(), vec!["start", "generation 0"]);
+        assert_eq!(result.stop_reason, StopReason::Cancelled);
+        assert!(result
+            .hall_of_fame
+            .iter()
+            .all(|entry| entry.generation == 0));
+    }
+}
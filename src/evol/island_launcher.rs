@@ -0,0 +1,480 @@
+use std::{panic, thread};
+
+use super::{
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    evol_launcher::{sort_fitness, EvolutionLauncher, EvolutionResult, Fitness},
+    evol_options::{IslandOptions, MigrationTopology},
+    generation_model::GenerationModel,
+    hall_of_fame::HallOfFame,
+    rand::RandomNumberGenerator,
+    scorer::{Scorer, SerialScorer},
+    termination::StopReason,
+    traits::{EvolutionOptionsTrait, EvolutionStrategy, Fingerprint, Phenotype},
+};
+
+pub struct Island<Pheno, EvolOptions, Strategy, Scoring = SerialScorer<Pheno, f64>>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
+{
+    launcher: EvolutionLauncher<Pheno, EvolOptions, Strategy, Scoring>,
+    evol_options: EvolOptions,
+    rng: RandomNumberGenerator,
+}
+
+#[derive(Clone)]
+pub struct IslandResult<Pheno: Phenotype> {
+    pub winner: EvolutionResult<Pheno>,
+    pub island_winners: Vec<EvolutionResult<Pheno>>,
+}
+
+pub struct IslandLauncher<Pheno, EvolOptions, Strategy, Scoring = SerialScorer<Pheno, f64>>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
+{
+    islands: Vec<Island<Pheno, EvolOptions, Strategy, Scoring>>,
+    island_options: IslandOptions,
+}
+
+impl<Pheno, EvolOptions, Strategy, Scoring> IslandLauncher<Pheno, EvolOptions, Strategy, Scoring>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
+{
+    pub fn new(island_options: IslandOptions) -> Self {
+        Self {
+            islands: Vec::new(),
+            island_options,
+        }
+    }
+
+    pub fn add_island(
+        &mut self,
+        launcher: EvolutionLauncher<Pheno, EvolOptions, Strategy, Scoring>,
+        evol_options: EvolOptions,
+        rng: RandomNumberGenerator,
+    ) {
+        self.islands.push(Island {
+            launcher,
+            evol_options,
+            rng,
+        });
+    }
+
+    pub fn get_num_islands(&self) -> usize {
+        self.islands.len()
+    }
+
+    // every island starts from the same value, their own rng streams let them drift apart; the islands
+    // run one after the other
+    pub fn evolve(&mut self, starting_value: Pheno) -> Result<IslandResult<Pheno>, EvolutionError>
+    where
+        Pheno: Fingerprint,
+    {
+        self.evolve_with(
+            starting_value,
+            &|islands, runs, first_generation, last_generation| {
+                islands
+                    .iter_mut()
+                    .zip(runs.iter_mut())
+                    .try_for_each(|(island, run)| {
+                        island.run(run, first_generation, last_generation)
+                    })
+            },
+        )
+    }
+
+    // like evolve, but every island runs on its own thread between two migrations, the result is the
+    // same; islands with a serial launcher are not Send, their launchers have to be created with
+    // new_parallel
+    pub fn evolve_parallel(
+        &mut self,
+        starting_value: Pheno,
+    ) -> Result<IslandResult<Pheno>, EvolutionError>
+    where
+        Pheno: Fingerprint + Send,
+        Island<Pheno, EvolOptions, Strategy, Scoring>: Send,
+    {
+        self.evolve_with(
+            starting_value,
+            &|islands, runs, first_generation, last_generation| {
+                thread::scope(|scope| {
+                    let handles: Vec<_> = islands
+                        .iter_mut()
+                        .zip(runs.iter_mut())
+                        .map(|(island, run)| {
+                            scope.spawn(move || island.run(run, first_generation, last_generation))
+                        })
+                        .collect();
+                    handles.into_iter().try_for_each(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+                    })
+                })
+            },
+        )
+    }
+
+    fn evolve_with(
+        &mut self,
+        starting_value: Pheno,
+        run_islands: &RunIslandsFn<Pheno, EvolOptions, Strategy, Scoring>,
+    ) -> Result<IslandResult<Pheno>, EvolutionError>
+    where
+        Pheno: Fingerprint,
+    {
+        if self.islands.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        // the populations of the islands are bred generation by generation to exchange migrants
+        if self.islands.iter().any(|island| {
+            island.evol_options.get_generation_model() != GenerationModel::Generational
+        }) {
+            return Err(EvolutionError::InvalidOptions(
+                "islands need generational runs".to_string(),
+            ));
+        }
+        for island in self.islands.iter() {
+            island.launcher.check_termination(&island.evol_options)?;
+        }
+        let mut runs: Vec<IslandRun<Pheno>> = self
+            .islands
+            .iter()
+            .map(|island| IslandRun {
+                evol_coordinator: EvolutionCoordinator::new(&island.evol_options),
+                parents: vec![starting_value.clone()],
+                fitness: Vec::new(),
+                hall_of_fame: HallOfFame::new(island.evol_options.get_hall_of_fame_size()),
+                stop_reason: None,
+                last_generation: 0,
+            })
+            .collect();
+        for island in self.islands.iter() {
+            island.launcher.start_run();
+        }
+
+        // islands that stopped keep their last population and receive no more migrants
+        let migration_interval = self.island_options.get_migration_interval();
+        let mut generation: usize = 0;
+        while runs.iter().any(|run| run.stop_reason.is_none()) {
+            let last_generation = if migration_interval > 0 {
+                generation.saturating_add(migration_interval)
+            } else {
+                usize::MAX
+            };
+            run_islands(
+                &mut self.islands,
+                &mut runs,
+                generation.saturating_add(1),
+                last_generation,
+            )?;
+            generation = last_generation;
+            // the migration is due if an island was still running when it was
+            if runs
+                .iter()
+                .any(|run| run.last_generation == last_generation)
+            {
+                self.migrate(&mut runs);
+            }
+            for (island, run) in self.islands.iter_mut().zip(runs.iter_mut()) {
+                if run.stop_reason.is_none() {
+                    run.parents = island.launcher.select_parents(
+                        &run.fitness,
+                        &island.evol_options,
+                        &mut island.rng,
+                    );
+                }
+            }
+        }
+
+        // every island ran at least one generation, so every island has a winner
+        let island_winners: Vec<EvolutionResult<Pheno>> = runs
+            .into_iter()
+            .map(|run| EvolutionResult {
+                winner: run.fitness[0].phenotype.clone(),
+                score: run.fitness[0].score,
+                stop_reason: run.stop_reason.unwrap(),
+                hall_of_fame: run.hall_of_fame.into_entries(),
+            })
+            .collect();
+        for (island, island_winner) in self.islands.iter().zip(island_winners.iter()) {
+            island.launcher.notify_finish(island_winner);
+        }
+        let winner = island_winners
+            .iter()
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+            .unwrap()
+            .clone();
+        Ok(IslandResult {
+            winner,
+            island_winners,
+        })
+    }
+
+    // the migrants join the scored population of their destination before its parents are selected,
+    // islands that stopped send migrants but do not take any in
+    fn migrate(&mut self, runs: &mut [IslandRun<Pheno>]) {
+        let num_islands = self.islands.len();
+        if num_islands < 2 {
+            return;
+        }
+        let num_migrants = self.island_options.get_num_migrants();
+        let mut arrivals: Vec<Vec<Fitness<Pheno>>> = vec![Vec::new(); num_islands];
+        for (source, (island, run)) in self.islands.iter_mut().zip(runs.iter()).enumerate() {
+            let migrants: Vec<Fitness<Pheno>> =
+                run.fitness.iter().take(num_migrants).cloned().collect();
+            let destinations: Vec<usize> = match self.island_options.get_topology() {
+                MigrationTopology::Ring => vec![(source + 1) % num_islands],
+                MigrationTopology::FullyConnected => {
+                    (0..num_islands).filter(|&i| i != source).collect()
+                }
+                MigrationTopology::Random => {
+                    let offset = 1 + island.rng.fetch_index(num_islands - 1);
+                    vec![(source + offset) % num_islands]
+                }
+            };
+            for destination in destinations {
+                arrivals[destination].extend(migrants.iter().cloned());
+            }
+        }
+        for (run, migrants) in runs.iter_mut().zip(arrivals) {
+            if run.stop_reason.is_some() {
+                continue;
+            }
+            run.fitness.extend(migrants);
+            sort_fitness(&mut run.fitness);
+        }
+    }
+}
+
+impl<Pheno, EvolOptions, Strategy, Scoring> Island<Pheno, EvolOptions, Strategy, Scoring>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, f64>,
+{
+    // runs the generations from first_generation to last_generation of the island launcher unless the
+    // island stops before, the parents for the generation after last_generation are selected after the
+    // migration
+    fn run(
+        &mut self,
+        run: &mut IslandRun<Pheno>,
+        first_generation: usize,
+        last_generation: usize,
+    ) -> Result<(), EvolutionError> {
+        for generation in first_generation..=last_generation {
+            if run.stop_reason.is_some() {
+                break;
+            }
+            run.last_generation = generation;
+            // at least one generation is needed to have a winner
+            run.stop_reason = run
+                .evol_coordinator
+                .get_stop_reason(&self.evol_options.get_termination())
+                .filter(|_| !run.fitness.is_empty());
+            if run.stop_reason.is_some() {
+                break;
+            }
+            run.fitness = self.launcher.evolve_generation(
+                &run.parents,
+                &self.evol_options,
+                &mut run.evol_coordinator,
+                &mut self.rng,
+            )?;
+            run.hall_of_fame
+                .record(&run.fitness, run.evol_coordinator.get_current_generation());
+            if generation < last_generation {
+                run.parents =
+                    self.launcher
+                        .select_parents(&run.fitness, &self.evol_options, &mut self.rng);
+            }
+        }
+        Ok(())
+    }
+}
+
+// the state of the run of one island
+struct IslandRun<Pheno: Phenotype> {
+    evol_coordinator: EvolutionCoordinator,
+    parents: Vec<Pheno>,
+    fitness: Vec<Fitness<Pheno>>,
+    hall_of_fame: HallOfFame<Pheno>,
+    stop_reason: Option<StopReason>,
+    last_generation: usize, // the last generation of the island launcher the island took part in
+}
+
+// runs every island from the first to the last generation of the island launcher
+type RunIslandsFn<Pheno, EvolOptions, Strategy, Scoring> = dyn Fn(
+    &mut [Island<Pheno, EvolOptions, Strategy, Scoring>],
+    &mut [IslandRun<Pheno>],
+    usize,
+    usize,
+) -> Result<(), EvolutionError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        error::EvolutionError,
+        evol_launcher::EvolutionLauncher,
+        evol_options::{EvolutionOptions, IslandOptions, MigrationTopology},
+        generation_model::{GenerationModel, Replacement},
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy,
+        rand::RandomNumberGenerator,
+        scorer::ParallelScorer,
+        termination::TerminationCriteria,
+        test_evol::{XCoordinate, XCoordinateChallenge},
+    };
+
+    use super::IslandLauncher;
+
+    #[test]
+    fn test_islands() {
+        for topology in [
+            MigrationTopology::Ring,
+            MigrationTopology::FullyConnected,
+            MigrationTopology::Random,
+        ] {
+            let mut island_launcher: IslandLauncher<
+                XCoordinate,
+                EvolutionOptions,
+                OrdinaryEvolutionStrategy,
+            > = IslandLauncher::new(IslandOptions::new(10, 2, topology));
+            for stream in 0..3 {
+                let challenge = XCoordinateChallenge::new(2.0);
+                island_launcher.add_island(
+                    EvolutionLauncher::new(
+                        OrdinaryEvolutionStrategy,
+                        Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+                    ),
+                    EvolutionOptions::new(),
+                    RandomNumberGenerator::from_seed_and_stream(0, stream),
+                );
+            }
+            let result = island_launcher.evolve(XCoordinate::new(0.0)).unwrap();
+            assert_eq!(result.island_winners.len(), 3);
+            assert!((result.winner.winner.x() - 2.0).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_parallel_islands() {
+        let island_launcher = || {
+            let mut island_launcher: IslandLauncher<
+                XCoordinate,
+                EvolutionOptions,
+                OrdinaryEvolutionStrategy,
+                ParallelScorer<XCoordinate, f64>,
+            > = IslandLauncher::new(IslandOptions::new(7, 2, MigrationTopology::Random));
+            for stream in 0..4 {
+                let challenge = XCoordinateChallenge::new(2.0);
+                // the islands stop at different generations
+                let evol_options = EvolutionOptions::new()
+                    .with_termination(TerminationCriteria::new().with_max_stagnation(5 + stream));
+                island_launcher.add_island(
+                    EvolutionLauncher::new_parallel(
+                        OrdinaryEvolutionStrategy,
+                        Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+                    ),
+                    evol_options,
+                    RandomNumberGenerator::from_seed_and_stream(0, stream as u64),
+                );
+            }
+            island_launcher
+        };
+        let sequential = island_launcher().evolve(XCoordinate::new(0.0)).unwrap();
+        let parallel = island_launcher()
+            .evolve_parallel(XCoordinate::new(0.0))
+            .unwrap();
+        for (sequential, parallel) in sequential
+            .island_winners
+            .iter()
+            .zip(parallel.island_winners.iter())
+        {
+            assert_eq!(
+                sequential.winner.x().to_bits(),
+                parallel.winner.x().to_bits()
+            );
+            assert_eq!(sequential.stop_reason, parallel.stop_reason);
+        }
+    }
+
+    #[test]
+    fn test_steady_state_islands() {
+        let mut island_launcher: IslandLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = IslandLauncher::new(IslandOptions::new(10, 2, MigrationTopology::Ring));
+        for (stream, generation_model) in [
+            GenerationModel::Generational,
+            GenerationModel::SteadyState {
+                num_offspring: 2,
+                replacement: Replacement::Worst,
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let challenge = XCoordinateChallenge::new(2.0);
+            island_launcher.add_island(
+                EvolutionLauncher::new(
+                    OrdinaryEvolutionStrategy,
+                    Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+                ),
+                EvolutionOptions::new().with_generation_model(generation_model),
+                RandomNumberGenerator::from_seed_and_stream(0, stream as u64),
+            );
+        }
+        assert!(matches!(
+            island_launcher.evolve(XCoordinate::new(0.0)),
+            Err(EvolutionError::InvalidOptions(_))
+        ));
+    }
+
+    #[test]
+    fn test_stopped_island() {
+        let mut island_launcher: IslandLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = IslandLauncher::new(IslandOptions::new(usize::MAX, 2, MigrationTopology::Ring));
+        let mut island_launcher_with_interval: IslandLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = IslandLauncher::new(IslandOptions::new(5, 2, MigrationTopology::Ring));
+        for launcher in [&mut island_launcher, &mut island_launcher_with_interval] {
+            // the first island stops after its first generation, the second one keeps sending migrants
+            for (stream, num_generations) in [(0, 1), (1, 100)] {
+                let challenge = XCoordinateChallenge::new(2.0);
+                launcher.add_island(
+                    EvolutionLauncher::new(
+                        OrdinaryEvolutionStrategy,
+                        Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+                    ),
+                    EvolutionOptions::new().with_num_generations(num_generations),
+                    RandomNumberGenerator::from_seed_and_stream(0, stream),
+                );
+            }
+            let result = launcher.evolve(XCoordinate::new(0.0)).unwrap();
+            let stopped = &result.island_winners[0];
+            // the winner of the stopped island is still one of its own
+            assert_eq!(stopped.score, stopped.hall_of_fame[0].score);
+            assert!(stopped
+                .hall_of_fame
+                .iter()
+                .all(|entry| entry.generation == 1));
+            assert!((result.winner.winner.x() - 2.0).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,579 @@
+use std::{cell::RefCell, marker::PhantomData, sync::Arc};
+
+use crate::{
+    canvas::{Canvas, PositivePoint},
+    math2d::point::Point,
+};
+
+use super::{
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    evol_launcher::{sanitize_score, Fitness},
+    evol_options::MapElitesOptions,
+    observer::{LogObserver, MapElitesObserver, ObserverAction},
+    rand::RandomNumberGenerator,
+    scorer::{ParallelScorer, ScoreFn, Scorer, SerialScorer, SyncScoreFn},
+    termination::StopReason,
+    traits::{EvolutionOptionsTrait, Phenotype},
+};
+
+// the features are evaluated like the scores, on the threads of a parallel launcher
+pub type FeatureFn<Pheno> = SyncScoreFn<Pheno, Vec<f64>>;
+
+// the number of heat levels of a rendered archive, 1 is the lowest score and 9 the highest
+const HEAT_LEVELS: i32 = 9;
+
+// a grid over the feature space that keeps the best phenotype of every cell
+#[derive(Clone)]
+pub struct MapElitesArchive<Pheno: Phenotype> {
+    feature_bounds: Vec<(f64, f64)>,
+    num_cells: Vec<usize>,
+    cells: Vec<Option<Fitness<Pheno>>>, // the first feature varies fastest
+}
+
+impl<Pheno: Phenotype> MapElitesArchive<Pheno> {
+    // every feature needs a range with its lower below its upper bound and at least one cell
+    pub fn new(map_elites_options: &MapElitesOptions) -> Result<Self, EvolutionError> {
+        let feature_bounds = map_elites_options.get_feature_bounds().to_vec();
+        let num_cells = map_elites_options.get_num_cells().to_vec();
+        if num_cells.len() != feature_bounds.len() {
+            return Err(EvolutionError::InvalidOptions(format!(
+                "{} features have bounds but {} have a number of cells",
+                feature_bounds.len(),
+                num_cells.len()
+            )));
+        }
+        if num_cells.contains(&0) {
+            return Err(EvolutionError::InvalidOptions(
+                "every feature needs at least one cell".to_string(),
+            ));
+        }
+        if let Some(&(lower, upper)) = feature_bounds
+            .iter()
+            .find(|(lower, upper)| lower >= upper || lower.is_nan() || upper.is_nan())
+        {
+            return Err(EvolutionError::InvalidOptions(format!(
+                "the feature bounds ({}, {}) are empty",
+                lower, upper
+            )));
+        }
+        Ok(Self {
+            feature_bounds,
+            cells: vec![None; num_cells.iter().product()],
+            num_cells,
+        })
+    }
+
+    // the coordinates of the cell the features fall into, there has to be a feature per dimension
+    pub fn get_coordinates(&self, features: &[f64]) -> Result<Vec<usize>, EvolutionError> {
+        if features.len() != self.num_cells.len() {
+            return Err(EvolutionError::FeatureCountMismatch {
+                expected: self.num_cells.len(),
+                actual: features.len(),
+            });
+        }
+        Ok(self
+            .num_cells
+            .iter()
+            .zip(self.feature_bounds.iter())
+            .zip(features.iter())
+            .map(|((&num_cells, &(lower, upper)), &feature)| {
+                let position = (feature - lower) / (upper - lower) * num_cells as f64;
+                // NaN falls into the first cell
+                (position.max(0.0) as usize).min(num_cells.saturating_sub(1))
+            })
+            .collect())
+    }
+
+    // None for an empty cell and for coordinates outside of the grid
+    pub fn get_cell(&self, coordinates: &[usize]) -> Option<&Fitness<Pheno>> {
+        let inside = coordinates.len() == self.num_cells.len()
+            && coordinates
+                .iter()
+                .zip(self.num_cells.iter())
+                .all(|(coordinate, num_cells)| coordinate < num_cells);
+        if !inside {
+            return None;
+        }
+        self.cells[self.index(coordinates)].as_ref()
+    }
+
+    // returns whether the phenotype became the elite of its cell, a score of -inf never does
+    pub fn insert(
+        &mut self,
+        phenotype: Pheno,
+        score: f64,
+        features: &[f64],
+    ) -> Result<bool, EvolutionError> {
+        let index = self.index(&self.get_coordinates(features)?);
+        let improves = match &self.cells[index] {
+            Some(elite) => score > elite.score,
+            None => score > f64::NEG_INFINITY,
+        };
+        if improves {
+            self.cells[index] = Some(Fitness::new(phenotype, score));
+        }
+        Ok(improves)
+    }
+
+    // the coordinates and the elite of every filled cell
+    pub fn get_elites(&self) -> Vec<(Vec<usize>, &Fitness<Pheno>)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| Some((self.coordinates_of(index), cell.as_ref()?)))
+            .collect()
+    }
+
+    pub fn get_best(&self) -> Option<&Fitness<Pheno>> {
+        self.cells
+            .iter()
+            .flatten()
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+
+    pub fn get_num_cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn get_num_filled(&self) -> usize {
+        self.cells.iter().flatten().count()
+    }
+
+    // the share of filled cells
+    pub fn coverage(&self) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+        self.get_num_filled() as f64 / self.cells.len() as f64
+    }
+
+    // the sum of the scores of all elites, with non-negative scores it only grows
+    pub fn qd_score(&self) -> f64 {
+        self.cells.iter().flatten().map(|elite| elite.score).sum()
+    }
+
+    // draws the first two features as a heatmap with one pixel per cell, like the x of a Point the first
+    // feature runs down the rows, empty cells stay 0 and elites are drawn from 1 for the lowest to 9 for
+    // the highest score
+    pub fn to_canvas(&self) -> Canvas {
+        let num_rows = self.num_cells.first().copied().unwrap_or(1);
+        let num_columns = self.num_cells.get(1).copied().unwrap_or(1);
+        let scores = self.cells.iter().flatten().map(|elite| elite.score);
+        let lowest = scores.clone().fold(f64::INFINITY, f64::min);
+        let highest = scores.fold(f64::NEG_INFINITY, f64::max);
+        // cells that differ only in the further features share a pixel, the hottest one is drawn
+        let mut values = vec![0; num_rows * num_columns];
+        for (coordinates, elite) in self.get_elites() {
+            let heat = if highest > lowest {
+                (elite.score - lowest) / (highest - lowest)
+            } else {
+                1.0
+            };
+            let value = 1 + (heat * (HEAT_LEVELS - 1) as f64).round() as i32;
+            let pixel = coordinates[0] * num_columns + coordinates.get(1).copied().unwrap_or(0);
+            values[pixel] = values[pixel].max(value);
+        }
+        let mut canvas = Canvas::new(num_columns, num_rows);
+        for (pixel, &value) in values.iter().enumerate().filter(|(_, &value)| value > 0) {
+            let (row, column) = (pixel / num_columns, pixel % num_columns);
+            canvas.draw_pixel(
+                PositivePoint::from(Point::new(row as f32, column as f32)),
+                value,
+            );
+        }
+        canvas
+    }
+
+    fn index(&self, coordinates: &[usize]) -> usize {
+        coordinates
+            .iter()
+            .zip(self.num_cells.iter())
+            .rev()
+            .fold(0, |index, (coordinate, num_cells)| {
+                index * num_cells + coordinate
+            })
+    }
+
+    fn coordinates_of(&self, mut index: usize) -> Vec<usize> {
+        self.num_cells
+            .iter()
+            .map(|num_cells| {
+                let coordinate = index % num_cells;
+                index /= num_cells;
+                coordinate
+            })
+            .collect()
+    }
+}
+
+pub struct MapElitesResult<Pheno: Phenotype> {
+    pub archive: MapElitesArchive<Pheno>,
+    pub winner: Pheno, // the best elite of the archive
+    pub score: f64,
+    pub coverage: f64,
+    pub qd_score: f64,
+    pub stop_reason: StopReason,
+}
+
+// quality diversity search, every generation breeds num_children children from random elites and each
+// child competes only with the elite of its own cell; it scores on the calling thread unless it is
+// created with new_parallel
+pub struct MapElitesLauncher<Pheno, EvolOptions, Scoring = SerialScorer<Pheno, f64>>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Scoring: Scorer<Pheno, f64>,
+{
+    scorer: Scoring,
+    feature_fn: Arc<FeatureFn<Pheno>>,
+    map_elites_options: MapElitesOptions,
+    observers: RefCell<Vec<Box<dyn MapElitesObserver<Pheno> + Send>>>,
+    _marker: PhantomData<EvolOptions>,
+}
+
+impl<Pheno, EvolOptions> MapElitesLauncher<Pheno, EvolOptions>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+{
+    pub fn new(
+        score_fn: Box<ScoreFn<Pheno, f64>>,
+        feature_fn: Box<FeatureFn<Pheno>>,
+        map_elites_options: MapElitesOptions,
+    ) -> Self {
+        Self::with_scorer(SerialScorer::new(score_fn), feature_fn, map_elites_options)
+    }
+}
+
+impl<Pheno, EvolOptions> MapElitesLauncher<Pheno, EvolOptions, ParallelScorer<Pheno, f64>>
+where
+    Pheno: Phenotype + Send + 'static,
+    EvolOptions: EvolutionOptionsTrait,
+{
+    // scores the candidates on the num_threads of the options, the threads are kept between generations
+    pub fn new_parallel(
+        score_fn: Box<SyncScoreFn<Pheno, f64>>,
+        feature_fn: Box<FeatureFn<Pheno>>,
+        map_elites_options: MapElitesOptions,
+    ) -> Self {
+        Self::with_scorer(
+            ParallelScorer::new(score_fn),
+            feature_fn,
+            map_elites_options,
+        )
+    }
+}
+
+impl<Pheno, EvolOptions, Scoring> MapElitesLauncher<Pheno, EvolOptions, Scoring>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Scoring: Scorer<Pheno, f64>,
+{
+    fn with_scorer(
+        scorer: Scoring,
+        feature_fn: Box<FeatureFn<Pheno>>,
+        map_elites_options: MapElitesOptions,
+    ) -> Self {
+        Self {
+            scorer,
+            feature_fn: Arc::from(feature_fn),
+            map_elites_options,
+            observers: RefCell::new(Vec::new()),
+            _marker: PhantomData,
+        }
+    }
+
+    // the observers see the archive after every generation
+    pub fn add_observer(&mut self, observer: Box<dyn MapElitesObserver<Pheno> + Send>) {
+        self.observers.get_mut().push(observer);
+    }
+
+    pub fn evolve(
+        &self,
+        evol_options: EvolOptions,
+        starting_value: Pheno,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<MapElitesResult<Pheno>, EvolutionError> {
+        let mut evol_coordinator = EvolutionCoordinator::new(&evol_options);
+        let mut archive = MapElitesArchive::new(&self.map_elites_options)?;
+        self.insert(&mut archive, vec![starting_value], &evol_options)?;
+        for observer in self.observers.borrow_mut().iter_mut() {
+            observer.on_start();
+        }
+        let stop_reason = loop {
+            if let Some(stop_reason) =
+                evol_coordinator.get_stop_reason(&evol_options.get_termination())
+            {
+                break stop_reason;
+            }
+            evol_coordinator.run();
+            let children = self.breed(&archive, &evol_options, evol_coordinator, rng)?;
+            let scores = self.insert(&mut archive, children, &evol_options)?;
+            evol_coordinator.record_scores(&scores, scores.len());
+            self.report_generation(&archive, &evol_options, &mut evol_coordinator);
+        };
+        let best = archive.get_best().ok_or(EvolutionError::EmptyPopulation)?;
+        Ok(MapElitesResult {
+            winner: best.phenotype.clone(),
+            score: best.score,
+            coverage: archive.coverage(),
+            qd_score: archive.qd_score(),
+            stop_reason,
+            archive,
+        })
+    }
+
+    fn breed(
+        &self,
+        archive: &MapElitesArchive<Pheno>,
+        evol_options: &EvolOptions,
+        evol_coordinator: EvolutionCoordinator,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        let elites: Vec<&Pheno> = archive
+            .cells
+            .iter()
+            .flatten()
+            .map(|elite| &elite.phenotype)
+            .collect();
+        if elites.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        Ok((0..evol_options.get_num_children())
+            .map(|_| {
+                let mut child = elites[rng.fetch_index(elites.len())].clone();
+                if rng.fetch_unit() < self.map_elites_options.get_crossover_rate() {
+                    child.crossover(elites[rng.fetch_index(elites.len())]);
+                }
+                child.mutate(rng, evol_coordinator);
+                child
+            })
+            .collect())
+    }
+
+    // tells the observers about the archive of a generation, any of them can end the run
+    fn report_generation(
+        &self,
+        archive: &MapElitesArchive<Pheno>,
+        evol_options: &EvolOptions,
+        evol_coordinator: &mut EvolutionCoordinator,
+    ) {
+        let generation = evol_coordinator.get_current_generation();
+        let mut action =
+            LogObserver::new(evol_options.get_log_level()).on_generation(generation, archive);
+        for observer in self.observers.borrow_mut().iter_mut() {
+            if observer.on_generation(generation, archive) == ObserverAction::Stop {
+                action = ObserverAction::Stop;
+            }
+        }
+        if action == ObserverAction::Stop {
+            evol_coordinator.cancel();
+        }
+    }
+
+    // scores the candidates and lets each of them compete for its cell, returns the scores
+    fn insert(
+        &self,
+        archive: &mut MapElitesArchive<Pheno>,
+        candidates: Vec<Pheno>,
+        evol_options: &EvolOptions,
+    ) -> Result<Vec<f64>, EvolutionError> {
+        let num_threads = evol_options.get_num_threads();
+        let candidate_refs: Vec<&Pheno> = candidates.iter().collect();
+        let scores = self.scorer.score(&candidate_refs, num_threads);
+        let features = self
+            .scorer
+            .evaluate(&candidate_refs, num_threads, &self.feature_fn);
+        let policy = evol_options.get_non_finite_score_policy();
+        let scores = scores
+            .into_iter()
+            .zip(candidates.iter())
+            .map(|(score, candidate)| sanitize_score(policy, score, candidate))
+            .collect::<Result<Vec<f64>, EvolutionError>>()?;
+        for ((candidate, &score), features) in
+            candidates.into_iter().zip(scores.iter()).zip(features)
+        {
+            archive.insert(candidate, score, &features)?;
+        }
+        Ok(scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::evol::{
+        error::EvolutionError,
+        evol_options::{EvolutionOptions, MapElitesOptions},
+        observer::{MapElitesObserver, ObserverAction},
+        rand::RandomNumberGenerator,
+        termination::StopReason,
+        test_evol::XCoordinates,
+    };
+
+    use super::{MapElitesArchive, MapElitesLauncher};
+
+    struct CoverageObserver {
+        coverages: Arc<Mutex<Vec<f64>>>,
+    }
+
+    impl MapElitesObserver<XCoordinates> for CoverageObserver {
+        fn on_generation(
+            &mut self,
+            generation: usize,
+            archive: &MapElitesArchive<XCoordinates>,
+        ) -> ObserverAction {
+            self.coverages.lock().unwrap().push(archive.coverage());
+            if generation == 5 {
+                return ObserverAction::Stop;
+            }
+            ObserverAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_archive() {
+        let options =
+            MapElitesOptions::new(vec![(0.0, 1.0), (0.0, 2.0)], 4).with_num_cells(vec![4, 2]);
+        let mut archive = MapElitesArchive::new(&options).unwrap();
+        assert_eq!(archive.get_coordinates(&[0.3, 1.5]).unwrap(), vec![1, 1]);
+        // features beyond the bounds fall into the edge cells
+        assert_eq!(archive.get_coordinates(&[-1.0, 5.0]).unwrap(), vec![0, 1]);
+        assert!(archive
+            .insert(XCoordinates::new(vec![0.3]), 1.0, &[0.3, 1.5])
+            .unwrap());
+        assert!(!archive
+            .insert(XCoordinates::new(vec![0.4]), 0.5, &[0.4, 1.5])
+            .unwrap());
+        assert!(archive
+            .insert(XCoordinates::new(vec![0.9]), 3.0, &[0.9, 0.5])
+            .unwrap());
+        assert!(!archive
+            .insert(XCoordinates::new(vec![0.0]), f64::NEG_INFINITY, &[0.0, 0.0])
+            .unwrap());
+        assert_eq!(
+            archive.get_cell(&[1, 1]).unwrap().phenotype.xs(),
+            &vec![0.3]
+        );
+        // the index of [5, 0] is the one of [1, 1]
+        assert!(archive.get_cell(&[5, 0]).is_none());
+        assert!(archive.get_cell(&[1]).is_none());
+        // a missing feature is not taken as the lower bound
+        assert!(matches!(
+            archive.insert(XCoordinates::new(vec![0.3]), 5.0, &[0.3]),
+            Err(EvolutionError::FeatureCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert_eq!(archive.get_num_filled(), 2);
+        assert_eq!(archive.coverage(), 0.25);
+        assert_eq!(archive.qd_score(), 4.0);
+        // the first feature runs down the rows
+        let canvas = archive.to_canvas();
+        assert_eq!((canvas.get_width(), canvas.get_height()), (2, 4));
+        assert_eq!(canvas.get_heatmap(), "..\n.1\n..\n9.\n");
+    }
+
+    #[test]
+    fn test_map_elites() {
+        let launcher: MapElitesLauncher<XCoordinates, EvolutionOptions> = MapElitesLauncher::new(
+            Box::new(|phenotype: &XCoordinates| {
+                10.0 - phenotype.xs().iter().map(|x| x * x).sum::<f64>()
+            }),
+            Box::new(|phenotype: &XCoordinates| phenotype.xs().clone()),
+            MapElitesOptions::new(vec![(-5.0, 5.0), (-5.0, 5.0)], 10),
+        );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = launcher
+            .evolve(
+                EvolutionOptions::new(),
+                XCoordinates::new(vec![0.0, 0.0]),
+                &mut rng,
+            )
+            .unwrap();
+        assert!(result.coverage > 0.8);
+        for (coordinates, elite) in result.archive.get_elites() {
+            assert_eq!(
+                result
+                    .archive
+                    .get_coordinates(elite.phenotype.xs())
+                    .unwrap(),
+                coordinates
+            );
+        }
+        // the starting value is the best phenotype of its cell and of the whole archive
+        assert_eq!(result.score, 10.0);
+        assert!(
+            (result.qd_score
+                - result
+                    .archive
+                    .get_elites()
+                    .iter()
+                    .map(|(_, elite)| elite.score)
+                    .sum::<f64>())
+            .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        for options in [
+            MapElitesOptions::new(vec![(0.0, 1.0), (0.0, 1.0)], 0),
+            MapElitesOptions::new(vec![(0.0, 1.0), (0.0, 1.0)], 4).with_num_cells(vec![4]),
+            MapElitesOptions::new(vec![(0.0, 1.0)], 4).with_num_cells(vec![4, 4]),
+            MapElitesOptions::new(vec![(1.0, 1.0)], 4),
+            MapElitesOptions::new(vec![(0.0, f64::NAN)], 4),
+        ] {
+            assert!(matches!(
+                MapElitesArchive::<XCoordinates>::new(&options),
+                Err(EvolutionError::InvalidOptions(_))
+            ));
+            let launcher: MapElitesLauncher<XCoordinates, EvolutionOptions> =
+                MapElitesLauncher::new(
+                    Box::new(|_: &XCoordinates| 0.0),
+                    Box::new(|phenotype: &XCoordinates| phenotype.xs().clone()),
+                    options,
+                );
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            assert!(matches!(
+                launcher.evolve(
+                    EvolutionOptions::new(),
+                    XCoordinates::new(vec![0.0]),
+                    &mut rng
+                ),
+                Err(EvolutionError::InvalidOptions(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let coverages = Arc::new(Mutex::new(Vec::new()));
+        let mut launcher: MapElitesLauncher<XCoordinates, EvolutionOptions> =
+            MapElitesLauncher::new(
+                Box::new(|_: &XCoordinates| 0.0),
+                Box::new(|phenotype: &XCoordinates| phenotype.xs().clone()),
+                MapElitesOptions::new(vec![(-5.0, 5.0)], 10),
+            );
+        launcher.add_observer(Box::new(CoverageObserver {
+            coverages: coverages.clone(),
+        }));
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = launcher
+            .evolve(
+                EvolutionOptions::new(),
+                XCoordinates::new(vec![0.0]),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(result.stop_reason, StopReason::Cancelled);
+        let coverages = coverages.lock().unwrap();
+        assert_eq!(coverages.len(), 5);
+        assert_eq!(*coverages.last().unwrap(), result.coverage);
+    }
+}
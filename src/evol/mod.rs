@@ -1,8 +1,31 @@
+pub mod checkpoint;
+pub mod cma_es_strategy;
+pub mod differential_evol_strategy;
+pub mod error;
 pub mod evol_coordinator;
 pub mod evol_launcher;
 pub mod evol_options;
+pub mod fitness_cache;
+pub mod generation_model;
+pub mod genomes;
+pub mod hall_of_fame;
+pub mod initialisation;
+pub mod island_launcher;
+pub mod map_elites;
+pub mod mu_lambda_strategy;
+pub mod multi_objective_launcher;
+pub mod mutation_schedule;
+pub mod novelty;
+pub mod observer;
+pub mod operators;
 pub mod ordinary_evol_strategy;
 pub mod partial_evol_strategy;
 pub mod rand;
+pub mod score_policy;
+pub mod scorer;
+pub mod selection;
+pub mod speciation_strategy;
+pub mod termination;
+#[cfg(test)]
 mod test_evol;
 pub mod traits;
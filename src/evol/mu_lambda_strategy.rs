@@ -0,0 +1,121 @@
+use super::{
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    rand::RandomNumberGenerator,
+    traits::{EvolutionOptionsTrait, EvolutionStrategy, Phenotype},
+};
+
+// which candidates the next mu parents are selected from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EsScheme {
+    Comma, // (mu, lambda): only the offspring, every parent lives for one generation
+    Plus, // (mu + lambda): the parents and the offspring, a good parent survives until it is beaten
+}
+
+// the classic evolution strategy with mu = num_parents and lambda = num_children, the launcher's selection
+// should be Truncation to pick the mu best; every offspring is a mutation of a random parent, recombined
+// with another random parent first if recombination is on; with a self-adapting phenotype such as the
+// SelfAdaptiveRealVector the mutation strengths evolve along with the values
+pub struct MuLambdaStrategy {
+    scheme: EsScheme,
+    recombination: bool,
+}
+
+impl MuLambdaStrategy {
+    pub fn new(scheme: EsScheme) -> Self {
+        Self {
+            scheme,
+            recombination: true,
+        }
+    }
+
+    pub fn with_recombination(mut self, recombination: bool) -> Self {
+        self.recombination = recombination;
+        self
+    }
+
+    pub fn get_scheme(&self) -> EsScheme {
+        self.scheme
+    }
+}
+
+impl<Pheno, EvolOptions> EvolutionStrategy<Pheno, EvolOptions> for MuLambdaStrategy
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+{
+    fn breed(
+        &self,
+        parents: Vec<Pheno>,
+        rng: &mut RandomNumberGenerator,
+        evol_coordinator: EvolutionCoordinator,
+        evol_options: &EvolOptions,
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        if parents.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        let mut children = Vec::with_capacity(parents.len() + evol_options.get_num_children());
+        // the parents are scored again together with their offspring
+        if self.scheme == EsScheme::Plus {
+            children.extend(parents.iter().cloned());
+        }
+        for _ in 0..evol_options.get_num_children() {
+            let mut child = parents[rng.fetch_index(parents.len())].clone();
+            if self.recombination && parents.len() > 1 {
+                child.crossover(&parents[rng.fetch_index(parents.len())]);
+            }
+            child.mutate(rng, evol_coordinator);
+            children.push(child);
+        }
+        Ok(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        evol_launcher::EvolutionLauncher, evol_options::EvolutionOptions,
+        genomes::self_adaptive_real_vector::SelfAdaptiveRealVector, rand::RandomNumberGenerator,
+        traits::RealVectorPhenotype,
+    };
+
+    use super::{EsScheme, MuLambdaStrategy};
+
+    #[test]
+    fn test_sphere() {
+        for scheme in [EsScheme::Comma, EsScheme::Plus] {
+            let launcher: EvolutionLauncher<
+                SelfAdaptiveRealVector,
+                EvolutionOptions,
+                MuLambdaStrategy,
+            > = EvolutionLauncher::new(
+                MuLambdaStrategy::new(scheme),
+                Box::new(|phenotype: &SelfAdaptiveRealVector| {
+                    -phenotype
+                        .get_values()
+                        .iter()
+                        .map(|value| (value - 0.3) * (value - 0.3))
+                        .sum::<f64>()
+                }),
+            );
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            let starting_value = SelfAdaptiveRealVector::random(vec![(-1.0, 1.0); 5], &mut rng);
+            let evol_options = EvolutionOptions::new()
+                .with_num_generations(200)
+                .with_num_parents(5)
+                .with_num_children(35);
+            let winner = launcher
+                .evolve(evol_options, starting_value, &mut rng)
+                .unwrap()
+                .winner;
+            for value in winner.get_values() {
+                assert!((value - 0.3).abs() < 1e-4);
+            }
+            // the step sizes shrank from 0.1 as the population closed in on the optimum
+            assert!(winner
+                .get_step_sizes()
+                .iter()
+                .all(|step_size| *step_size < 1e-2));
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use super::{
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    evol_launcher::sanitize_score,
+    observer::LogObserver,
+    rand::RandomNumberGenerator,
+    scorer::{ParallelScorer, ScoreFn, Scorer, SerialScorer, SyncScoreFn},
+    traits::{EvolutionOptionsTrait, EvolutionStrategy, Fingerprint, Phenotype},
+};
+
+#[derive(Clone)]
+pub struct MultiObjectiveResult<Pheno: Phenotype> {
+    pub winner: Pheno,
+    pub scores: Vec<f64>,
+    pub rank: usize, // index of the non dominated front, 0 is the pareto front
+    pub crowding_distance: f64,
+}
+
+// all objectives are maximised, just like the score of the EvolutionLauncher; it scores on the calling
+// thread unless it is created with new_parallel
+pub struct MultiObjectiveLauncher<
+    Pheno,
+    EvolOptions,
+    Strategy,
+    Scoring = SerialScorer<Pheno, Vec<f64>>,
+> where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, Vec<f64>>,
+{
+    strategy: Strategy,
+    scorer: Scoring,
+    _marker: PhantomData<(Pheno, EvolOptions)>,
+}
+
+impl<Pheno, EvolOptions, Strategy> MultiObjectiveLauncher<Pheno, EvolOptions, Strategy>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+{
+    pub fn new(strategy: Strategy, score_fn: Box<ScoreFn<Pheno, Vec<f64>>>) -> Self {
+        Self::with_scorer(strategy, SerialScorer::new(score_fn))
+    }
+}
+
+impl<Pheno, EvolOptions, Strategy>
+    MultiObjectiveLauncher<Pheno, EvolOptions, Strategy, ParallelScorer<Pheno, Vec<f64>>>
+where
+    Pheno: Phenotype + Send + 'static,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+{
+    // scores the candidates on the num_threads of the options, the threads are kept between generations
+    pub fn new_parallel(strategy: Strategy, score_fn: Box<SyncScoreFn<Pheno, Vec<f64>>>) -> Self {
+        Self::with_scorer(strategy, ParallelScorer::new(score_fn))
+    }
+}
+
+impl<Pheno, EvolOptions, Strategy, Scoring>
+    MultiObjectiveLauncher<Pheno, EvolOptions, Strategy, Scoring>
+where
+    Pheno: Phenotype,
+    EvolOptions: EvolutionOptionsTrait,
+    Strategy: EvolutionStrategy<Pheno, EvolOptions>,
+    Scoring: Scorer<Pheno, Vec<f64>>,
+{
+    fn with_scorer(strategy: Strategy, scorer: Scoring) -> Self {
+        Self {
+            strategy,
+            scorer,
+            _marker: PhantomData,
+        }
+    }
+
+    // NSGA-II, the parents are picked by binary tournaments from the best num_parents of the parents and
+    // children of the previous generation; the returned pareto front holds every phenotype once; every
+    // phenotype needs as many objectives as the starting value, at least one; the termination criteria of
+    // a single score (target score, stagnation and score spread) and the diversity do not apply and there
+    // are no observers to cancel the run
+    pub fn evolve(
+        &self,
+        evol_options: EvolOptions,
+        starting_value: Pheno,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<MultiObjectiveResult<Pheno>>, EvolutionError>
+    where
+        Pheno: Fingerprint,
+    {
+        if self.strategy.needs_scores() {
+            return Err(EvolutionError::InvalidOptions(
+                "the strategy needs a single score per candidate".to_string(),
+            ));
+        }
+        let mut evol_coordinator = EvolutionCoordinator::new(&evol_options);
+        self.strategy.start_run(&[], &[]);
+        let mut population = self.score(vec![starting_value], &evol_options, None)?;
+        evol_coordinator.record_evaluations(population.len());
+        let num_objectives = population[0].scores.len();
+        if num_objectives == 0 {
+            return Err(EvolutionError::InvalidOptions(
+                "the score function returned no objectives".to_string(),
+            ));
+        }
+        rank_population(&mut population);
+        let mut pool = population.clone();
+
+        while evol_coordinator
+            .get_stop_reason(&evol_options.get_termination())
+            .is_none()
+        {
+            evol_coordinator.run();
+            if population.is_empty() {
+                return Err(EvolutionError::EmptyPopulation);
+            }
+            let parents = select_parents(&population, evol_options.get_num_parents().max(1), rng);
+            let children = self
+                .strategy
+                .breed(parents, rng, evol_coordinator, &evol_options)?;
+            let children = self.score(children, &evol_options, Some(num_objectives))?;
+            let num_children = children.len();
+            evol_coordinator.record_evaluations(num_children);
+            let child_fingerprints: HashSet<u64> = children
+                .iter()
+                .map(|result| result.winner.fingerprint())
+                .collect();
+            // parents compete with their children so the pareto front can not get worse
+            pool = population;
+            pool.extend(children);
+            // an unchanged clone of a parent would crowd the front with copies
+            let mut fingerprints = HashSet::new();
+            pool.retain(|result| fingerprints.insert(result.winner.fingerprint()));
+            rank_population(&mut pool);
+            LogObserver::new(evol_options.get_log_level())
+                .on_multi_objective_generation(evol_coordinator.get_current_generation(), &pool);
+            population = pool
+                .iter()
+                .take(evol_options.get_num_parents().max(1))
+                .cloned()
+                .collect();
+            // a child succeeds when it is selected as a parent of the next generation
+            let successes = population
+                .iter()
+                .filter(|result| child_fingerprints.contains(&result.winner.fingerprint()))
+                .count();
+            evol_coordinator.record_successes(successes, num_children);
+        }
+        Ok(pool.into_iter().filter(|result| result.rank == 0).collect())
+    }
+
+    // scores the candidates, each of them needs num_objectives objectives if given
+    fn score(
+        &self,
+        candidates: Vec<Pheno>,
+        evol_options: &EvolOptions,
+        num_objectives: Option<usize>,
+    ) -> Result<Vec<MultiObjectiveResult<Pheno>>, EvolutionError> {
+        let scores = self.scorer.score(
+            &candidates.iter().collect::<Vec<&Pheno>>(),
+            evol_options.get_num_threads(),
+        );
+        if let Some(expected) = num_objectives {
+            if let Some(objectives) = scores
+                .iter()
+                .find(|objectives| objectives.len() != expected)
+            {
+                return Err(EvolutionError::ObjectiveCountMismatch {
+                    expected,
+                    actual: objectives.len(),
+                });
+            }
+        }
+        let policy = evol_options.get_non_finite_score_policy();
+        let scores = scores
+            .into_iter()
+            .zip(candidates.iter())
+            .map(|(objectives, candidate)| {
+                objectives
+                    .into_iter()
+                    .map(|score| sanitize_score(policy, score, candidate))
+                    .collect::<Result<Vec<f64>, EvolutionError>>()
+            })
+            .collect::<Result<Vec<_>, EvolutionError>>()?;
+        Ok(candidates
+            .into_iter()
+            .zip(scores)
+            .map(|(winner, scores)| MultiObjectiveResult {
+                winner,
+                scores,
+                rank: 0,
+                crowding_distance: 0.0,
+            })
+            .collect())
+    }
+}
+
+// NSGA-II's binary tournament with the crowded comparison, the lower rank wins and the larger crowding
+// distance breaks ties
+fn select_parents<Pheno: Phenotype>(
+    population: &[MultiObjectiveResult<Pheno>],
+    num_parents: usize,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Pheno> {
+    (0..num_parents)
+        .map(|_| {
+            let first = &population[rng.fetch_index(population.len())];
+            let second = &population[rng.fetch_index(population.len())];
+            let first_wins = first.rank < second.rank
+                || (first.rank == second.rank
+                    && first.crowding_distance >= second.crowding_distance);
+            if first_wins {
+                first.winner.clone()
+            } else {
+                second.winner.clone()
+            }
+        })
+        .collect()
+}
+
+// sorts the population by front and by descending crowding distance within a front
+fn rank_population<Pheno: Phenotype>(population: &mut [MultiObjectiveResult<Pheno>]) {
+    let scores: Vec<Vec<f64>> = population
+        .iter()
+        .map(|result| result.scores.clone())
+        .collect();
+    let ranks = non_dominated_sort(&scores);
+    let crowding_distances = crowding_distances(&scores, &ranks);
+    for (i, result) in population.iter_mut().enumerate() {
+        result.rank = ranks[i];
+        result.crowding_distance = crowding_distances[i];
+    }
+    population.sort_by(|a, b| {
+        a.rank
+            .cmp(&b.rank)
+            .then(b.crowding_distance.total_cmp(&a.crowding_distance))
+    });
+}
+
+// score vectors of different lengths do not dominate each other
+pub fn dominates(first: &[f64], second: &[f64]) -> bool {
+    if first.len() != second.len() {
+        return false;
+    }
+    let mut strictly_better = false;
+    for (a, b) in first.iter().zip(second.iter()) {
+        if a < b {
+            return false;
+        }
+        if a > b {
+            strictly_better = true;
+        }
+    }
+    strictly_better
+}
+
+pub fn non_dominated_sort(scores: &[Vec<f64>]) -> Vec<usize> {
+    let mut ranks = vec![0; scores.len()];
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); scores.len()];
+    let mut domination_count = vec![0; scores.len()];
+    let mut front: Vec<usize> = Vec::new();
+    for i in 0..scores.len() {
+        for j in 0..scores.len() {
+            if dominates(&scores[i], &scores[j]) {
+                dominated_by[i].push(j);
+            } else if dominates(&scores[j], &scores[i]) {
+                domination_count[i] += 1;
+            }
+        }
+        if domination_count[i] == 0 {
+            front.push(i);
+        }
+    }
+    let mut rank = 0;
+    while !front.is_empty() {
+        let mut next_front = Vec::new();
+        for &i in front.iter() {
+            ranks[i] = rank;
+            for &j in dominated_by[i].iter() {
+                domination_count[j] -= 1;
+                if domination_count[j] == 0 {
+                    next_front.push(j);
+                }
+            }
+        }
+        front = next_front;
+        rank += 1;
+    }
+    ranks
+}
+
+// panics unless every score vector has the same length
+pub fn crowding_distances(scores: &[Vec<f64>], ranks: &[usize]) -> Vec<f64> {
+    let mut distances = vec![0.0; scores.len()];
+    let num_fronts = ranks.iter().max().map_or(0, |rank| rank + 1);
+    let num_objectives = scores.first().map_or(0, |score| score.len());
+    assert!(
+        scores.iter().all(|score| score.len() == num_objectives),
+        "every score vector needs {} objectives",
+        num_objectives
+    );
+    // the values of one objective for all of the population
+    let objectives: Vec<Vec<f64>> = (0..num_objectives)
+        .map(|objective| scores.iter().map(|score| score[objective]).collect())
+        .collect();
+    for rank in 0..num_fronts {
+        let mut front: Vec<usize> = (0..scores.len()).filter(|&i| ranks[i] == rank).collect();
+        for values in objectives.iter() {
+            front.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+            let first = front[0];
+            let last = front[front.len() - 1];
+            distances[first] = f64::INFINITY;
+            distances[last] = f64::INFINITY;
+            let range = values[last] - values[first];
+            if range <= 0.0 {
+                continue;
+            }
+            for neighbours in front.windows(3) {
+                distances[neighbours[1]] += (values[neighbours[2]] - values[neighbours[0]]) / range;
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        error::EvolutionError, evol_options::EvolutionOptions,
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy, rand::RandomNumberGenerator,
+        termination::TerminationCriteria, test_evol::XCoordinate,
+    };
+
+    use std::{
+        collections::HashSet,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use super::{
+        dominates, non_dominated_sort, select_parents, MultiObjectiveLauncher, MultiObjectiveResult,
+    };
+
+    #[test]
+    fn test_non_dominated_sort() {
+        let scores = vec![
+            vec![1.0, 1.0],
+            vec![2.0, 0.0],
+            vec![0.0, 2.0],
+            vec![0.5, 0.5],
+            vec![0.0, 0.0],
+        ];
+        assert_eq!(non_dominated_sort(&scores), vec![0, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_schaffer() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options = EvolutionOptions::new().with_num_parents(10);
+        let launcher: MultiObjectiveLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = MultiObjectiveLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(|phenotype: &XCoordinate| {
+                let x = phenotype.x();
+                vec![-x * x, -(x - 2.0) * (x - 2.0)]
+            }),
+        );
+        let front = launcher
+            .evolve(evol_options, XCoordinate::new(5.0), &mut rng)
+            .unwrap();
+        assert!(front.len() > 1);
+        for result in front.iter() {
+            assert!(result.winner.x() > -1e-2 && result.winner.x() < 2.0 + 1e-2);
+        }
+        let distinct: HashSet<u64> = front
+            .iter()
+            .map(|result| result.winner.x().to_bits())
+            .collect();
+        assert_eq!(distinct.len(), front.len());
+    }
+
+    #[test]
+    fn test_select_parents() {
+        let result = |x: f64, rank: usize, crowding_distance: f64| MultiObjectiveResult {
+            winner: XCoordinate::new(x),
+            scores: Vec::new(),
+            rank,
+            crowding_distance,
+        };
+        let population = vec![
+            result(0.0, 0, f64::INFINITY),
+            result(1.0, 0, 0.5),
+            result(2.0, 1, f64::INFINITY),
+        ];
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let parents = select_parents(&population, 3000, &mut rng);
+        let count = |x: f64| parents.iter().filter(|parent| parent.x() == x).count();
+        // each one wins the tournaments against the worse ones and against itself, 5/9, 3/9 and 1/9
+        assert!((count(0.0) as f64 / 3000.0 - 5.0 / 9.0).abs() < 0.05);
+        assert!((count(1.0) as f64 / 3000.0 - 3.0 / 9.0).abs() < 0.05);
+        assert!((count(2.0) as f64 / 3000.0 - 1.0 / 9.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_objective_counts() {
+        assert!(!dominates(&[2.0, 2.0], &[1.0]));
+        assert!(!dominates(&[1.0], &[0.0, 2.0]));
+        let evolve = |score_fn: fn(&XCoordinate) -> Vec<f64>| {
+            let launcher: MultiObjectiveLauncher<
+                XCoordinate,
+                EvolutionOptions,
+                OrdinaryEvolutionStrategy,
+            > = MultiObjectiveLauncher::new(OrdinaryEvolutionStrategy, Box::new(score_fn));
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            launcher.evolve(EvolutionOptions::new(), XCoordinate::new(0.0), &mut rng)
+        };
+        assert!(matches!(
+            evolve(|_| Vec::new()),
+            Err(EvolutionError::InvalidOptions(_))
+        ));
+        // only the starting value at 0 has two objectives
+        assert!(matches!(
+            evolve(|phenotype| if phenotype.x() == 0.0 {
+                vec![0.0, 0.0]
+            } else {
+                vec![phenotype.x()]
+            }),
+            Err(EvolutionError::ObjectiveCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_termination() {
+        let num_evaluations = Arc::new(AtomicUsize::new(0));
+        let counter = num_evaluations.clone();
+        let launcher: MultiObjectiveLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = MultiObjectiveLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(move |phenotype: &XCoordinate| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                vec![phenotype.x(), -phenotype.x()]
+            }),
+        );
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        // the starting value and two generations of 20 children
+        let evol_options = EvolutionOptions::new()
+            .with_termination(TerminationCriteria::new().with_max_evaluations(41));
+        launcher
+            .evolve(evol_options, XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        assert_eq!(num_evaluations.load(Ordering::Relaxed), 41);
+    }
+}
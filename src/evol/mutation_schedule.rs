@@ -0,0 +1,113 @@
+use std::f64::consts::PI;
+
+use super::evol_coordinator::EvolutionCoordinator;
+
+// computes the mutation step size of the current generation, so that searches
+// take big steps early and fine steps late
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MutationSchedule {
+    Constant(f64),
+    Linear { start: f64, end: f64 },
+    Exponential { start: f64, end: f64 }, // start and end must have the same sign
+    CosineAnnealing { start: f64, end: f64 },
+    // the step grows by factor while more than a fifth of the children beat the best parent, else it shrinks
+    OneFifthRule { initial: f64, factor: f64 },
+}
+
+impl MutationSchedule {
+    pub fn step_size(&self, evol_coordinator: &EvolutionCoordinator) -> f64 {
+        let progress = evol_coordinator.get_progress().clamp(0.0, 1.0);
+        match *self {
+            MutationSchedule::Constant(step_size) => step_size,
+            MutationSchedule::Linear { start, end } => start + (end - start) * progress,
+            MutationSchedule::Exponential { start, end } => start * (end / start).powf(progress),
+            MutationSchedule::CosineAnnealing { start, end } => {
+                end + (start - end) * (1.0 + (PI * progress).cos()) / 2.0
+            }
+            MutationSchedule::OneFifthRule { initial, factor } => {
+                initial * factor.powi(evol_coordinator.get_success_exponent())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{evol_coordinator::EvolutionCoordinator, evol_options::EvolutionOptions};
+
+    use super::MutationSchedule;
+
+    #[test]
+    fn test_schedules() {
+        let mut evol_coordinator = EvolutionCoordinator::new(&EvolutionOptions::new());
+        let schedules = [
+            MutationSchedule::Linear {
+                start: 1.0,
+                end: 0.01,
+            },
+            MutationSchedule::Exponential {
+                start: 1.0,
+                end: 0.01,
+            },
+            MutationSchedule::CosineAnnealing {
+                start: 1.0,
+                end: 0.01,
+            },
+        ];
+        for schedule in schedules.iter() {
+            assert!((schedule.step_size(&evol_coordinator) - 1.0).abs() < 1e-9);
+        }
+        let mut previous_step_sizes = vec![1.0; schedules.len()];
+        for _ in 0..100 {
+            evol_coordinator.run();
+            for (schedule, previous) in schedules.iter().zip(previous_step_sizes.iter_mut()) {
+                let step_size = schedule.step_size(&evol_coordinator);
+                assert!(step_size <= *previous);
+                *previous = step_size;
+            }
+        }
+        for step_size in previous_step_sizes {
+            assert!((step_size - 0.01).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_one_fifth_rule() {
+        let mut evol_coordinator = EvolutionCoordinator::new(&EvolutionOptions::new());
+        let schedule = MutationSchedule::OneFifthRule {
+            initial: 1.0,
+            factor: 2.0,
+        };
+        evol_coordinator.record_scores(&[1.0, 0.0, 0.0, 0.0, 0.0], 5);
+        assert_eq!(schedule.step_size(&evol_coordinator), 1.0);
+        // all children are better than the best score so far
+        evol_coordinator.record_scores(&[3.0, 2.0], 2);
+        assert_eq!(schedule.step_size(&evol_coordinator), 2.0);
+        // no child is better
+        evol_coordinator.record_scores(&[1.0, 1.0], 2);
+        evol_coordinator.record_scores(&[1.0, 1.0], 2);
+        assert_eq!(schedule.step_size(&evol_coordinator), 0.5);
+        // the step size neither vanishes nor overflows however long the failures or successes last
+        for _ in 0..2000 {
+            evol_coordinator.record_scores(&[1.0], 1);
+        }
+        assert!(schedule.step_size(&evol_coordinator) > 0.0);
+        for score in 0..2000 {
+            evol_coordinator.record_scores(&[2.0 + score as f64], 1);
+        }
+        assert!(schedule.step_size(&evol_coordinator).is_finite());
+    }
+
+    #[test]
+    fn test_one_fifth_rule_steady_state() {
+        let mut evol_coordinator = EvolutionCoordinator::new(&EvolutionOptions::new());
+        let schedule = MutationSchedule::OneFifthRule {
+            initial: 1.0,
+            factor: 2.0,
+        };
+        evol_coordinator.record_population(&[1.0, 0.0, 0.0, 0.0, 0.0], 5, 5);
+        // the one new member of five beats the best score, which is every child
+        evol_coordinator.record_population(&[2.0, 1.0, 0.0, 0.0, 0.0], 1, 1);
+        assert_eq!(schedule.step_size(&evol_coordinator), 2.0);
+    }
+}
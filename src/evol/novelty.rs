@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use super::{evol_options::NoveltyOptions, scorer::SyncScoreFn, traits::Phenotype};
+
+// the behaviour descriptors are evaluated like the scores, on the threads of a parallel launcher
+pub type BehaviourFn<Pheno> = SyncScoreFn<Pheno, Vec<f64>>;
+
+// ranks candidates by how far their behaviour is from the behaviours of the other candidates and of the
+// archive, the archive starts empty with every run and is restored with the ranking scores when a run is
+// resumed from a checkpoint
+pub struct NoveltySearch<Pheno: Phenotype> {
+    behaviour_fn: Arc<BehaviourFn<Pheno>>,
+    novelty_options: NoveltyOptions,
+    archive: Vec<Vec<f64>>, // the oldest behaviour comes first
+}
+
+impl<Pheno: Phenotype> NoveltySearch<Pheno> {
+    pub fn new(behaviour_fn: Box<BehaviourFn<Pheno>>, novelty_options: NoveltyOptions) -> Self {
+        Self {
+            behaviour_fn: Arc::from(behaviour_fn),
+            novelty_options,
+            archive: Vec::new(),
+        }
+    }
+
+    pub fn get_behaviour_fn(&self) -> &Arc<BehaviourFn<Pheno>> {
+        &self.behaviour_fn
+    }
+
+    pub fn get_archive(&self) -> &[Vec<f64>] {
+        &self.archive
+    }
+
+    pub fn clear_archive(&mut self) {
+        self.archive.clear();
+    }
+
+    pub fn restore_archive(&mut self, archive: Vec<Vec<f64>>) {
+        self.archive = archive;
+    }
+
+    // blends the novelty of every candidate's behaviour with its score and archives the most novel
+    // behaviours
+    pub fn rank(&mut self, behaviours: &[Vec<f64>], scores: &[f64]) -> Vec<f64> {
+        let novelties: Vec<f64> = (0..behaviours.len())
+            .map(|i| self.novelty(behaviours, i))
+            .collect();
+        let mut most_novel: Vec<usize> = (0..behaviours.len()).collect();
+        most_novel.sort_by(|&a, &b| novelties[b].total_cmp(&novelties[a]));
+        for &i in most_novel
+            .iter()
+            .take(self.novelty_options.get_num_archived())
+        {
+            self.archive.push(behaviours[i].clone());
+        }
+        let excess = self
+            .archive
+            .len()
+            .saturating_sub(self.novelty_options.get_max_archive_size());
+        self.archive.drain(..excess);
+        let objective_weight = self.novelty_options.get_objective_weight();
+        novelties
+            .iter()
+            .zip(scores.iter())
+            .map(|(novelty, score)| {
+                // an infinite score must not turn a pure novelty into NaN
+                if objective_weight > 0.0 {
+                    objective_weight * score + (1.0 - objective_weight) * novelty
+                } else {
+                    *novelty
+                }
+            })
+            .collect()
+    }
+
+    // the mean distance to the nearest neighbours among the other candidates and the archive
+    fn novelty(&self, behaviours: &[Vec<f64>], i: usize) -> f64 {
+        let mut distances: Vec<f64> = behaviours
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, other)| other)
+            .chain(self.archive.iter())
+            .map(|other| euclidean_distance(&behaviours[i], other))
+            .collect();
+        if distances.is_empty() {
+            return 0.0;
+        }
+        let num_neighbours = self
+            .novelty_options
+            .get_num_neighbours()
+            .clamp(1, distances.len());
+        distances.select_nth_unstable_by(num_neighbours - 1, f64::total_cmp);
+        distances[..num_neighbours].iter().sum::<f64>() / num_neighbours as f64
+    }
+}
+
+// the missing components of the shorter descriptor count as 0
+fn euclidean_distance(first: &[f64], second: &[f64]) -> f64 {
+    (0..first.len().max(second.len()))
+        .map(|i| {
+            let difference = first.get(i).unwrap_or(&0.0) - second.get(i).unwrap_or(&0.0);
+            difference * difference
+        })
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        evol_launcher::EvolutionLauncher,
+        evol_options::{EvolutionOptions, NoveltyOptions},
+        observer::{EvolutionObserver, GenerationReport, ObserverAction},
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy,
+        rand::RandomNumberGenerator,
+        termination::StopReason,
+        test_evol::XCoordinate,
+    };
+
+    use super::{euclidean_distance, NoveltySearch};
+
+    struct StopObserver {
+        generation: usize,
+    }
+
+    impl EvolutionObserver<XCoordinate> for StopObserver {
+        fn on_generation(&mut self, report: &GenerationReport<XCoordinate>) -> ObserverAction {
+            if report.generation == self.generation {
+                return ObserverAction::Stop;
+            }
+            ObserverAction::Continue
+        }
+    }
+
+    fn new_launcher() -> EvolutionLauncher<XCoordinate, EvolutionOptions, OrdinaryEvolutionStrategy>
+    {
+        EvolutionLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(|phenotype: &XCoordinate| -phenotype.x().abs()),
+        )
+        .with_novelty_search(
+            Box::new(|phenotype: &XCoordinate| vec![phenotype.x()]),
+            NoveltyOptions::new(5, 0.5),
+        )
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut novelty_search = NoveltySearch::new(
+            Box::new(|phenotype: &XCoordinate| vec![phenotype.x()]),
+            NoveltyOptions::new(2, 0.0).with_archive(1, 1),
+        );
+        let behaviours = [0.0, 1.0, 3.0].map(|x| vec![x]);
+        let novelties = novelty_search.rank(&behaviours, &[0.0; 3]);
+        assert_eq!(novelties, vec![2.0, 1.5, 2.5]);
+        assert_eq!(novelty_search.get_archive(), &[vec![3.0]]);
+        // the archived 3 is the nearest neighbour of the new 3
+        let novelties = novelty_search.rank(&behaviours, &[0.0; 3]);
+        assert_eq!(novelties, vec![2.0, 1.5, 1.0]);
+    }
+
+    #[test]
+    fn test_different_lengths() {
+        assert_eq!(euclidean_distance(&[3.0], &[0.0, 4.0]), 5.0);
+        assert_eq!(euclidean_distance(&[0.0, 4.0], &[3.0]), 5.0);
+        assert_eq!(euclidean_distance(&[], &[3.0, 4.0]), 5.0);
+    }
+
+    #[test]
+    fn test_deceptive() {
+        // the score leads back to 0 unless x is beyond 5
+        let deceptive = |phenotype: &XCoordinate| match phenotype.x().abs() {
+            distance if distance > 5.0 => distance,
+            distance => -distance,
+        };
+        let objective_launcher: EvolutionLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(OrdinaryEvolutionStrategy, Box::new(deceptive));
+        let novelty_launcher: EvolutionLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(OrdinaryEvolutionStrategy, Box::new(deceptive))
+            .with_novelty_search(
+                Box::new(|phenotype: &XCoordinate| vec![phenotype.x()]),
+                NoveltyOptions::new(5, 0.0),
+            );
+        let evolve = |launcher: &EvolutionLauncher<_, _, _>| {
+            let mut rng = RandomNumberGenerator::from_seed(0);
+            launcher
+                .evolve(EvolutionOptions::new(), XCoordinate::new(0.0), &mut rng)
+                .unwrap()
+        };
+        assert!(deceptive(&evolve(&objective_launcher).winner) <= 0.0);
+        // the winner and the hall of fame go by the score, not by the novelty
+        let result = evolve(&novelty_launcher);
+        assert!(result.winner.x().abs() > 5.0);
+        assert_eq!(result.score, deceptive(&result.winner));
+        for entry in result.hall_of_fame.iter() {
+            assert_eq!(entry.score, deceptive(&entry.phenotype));
+        }
+        let archive = novelty_launcher.get_novelty_archive();
+        assert!(archive.iter().any(|behaviour| behaviour[0].abs() > 5.0));
+        // every run starts with an empty archive
+        evolve(&novelty_launcher);
+        assert_eq!(novelty_launcher.get_novelty_archive(), archive);
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint_path =
+            std::env::temp_dir().join(format!("novelty_checkpoint_{}.txt", std::process::id()));
+        let evol_options = EvolutionOptions::new().with_num_generations(40);
+        let launcher = new_launcher();
+        let expected = launcher
+            .evolve(
+                evol_options,
+                XCoordinate::new(3.0),
+                &mut RandomNumberGenerator::from_seed(0),
+            )
+            .unwrap();
+        let expected_archive = launcher.get_novelty_archive();
+        // the run is cancelled after the checkpoint of generation 20 and resumed by a new launcher
+        let mut launcher = new_launcher();
+        launcher.add_observer(Box::new(StopObserver { generation: 20 }));
+        let cancelled = launcher
+            .evolve_with_checkpoints(
+                evol_options,
+                XCoordinate::new(3.0),
+                &mut RandomNumberGenerator::from_seed(0),
+                &checkpoint_path,
+                10,
+            )
+            .unwrap();
+        assert_eq!(cancelled.stop_reason, StopReason::Cancelled);
+        let launcher = new_launcher();
+        let resumed = launcher.resume(&checkpoint_path, 10).unwrap();
+        std::fs::remove_file(&checkpoint_path).unwrap();
+        assert_eq!(resumed.winner.x(), expected.winner.x());
+        assert_eq!(resumed.score, expected.score);
+        assert_eq!(launcher.get_novelty_archive(), expected_archive);
+    }
+}
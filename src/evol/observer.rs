@@ -0,0 +1,190 @@
+use super::{
+    evol_launcher::{EvolutionResult, Fitness},
+    map_elites::MapElitesArchive,
+    multi_objective_launcher::MultiObjectiveResult,
+    traits::Phenotype,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObserverAction {
+    Continue,
+    Stop, // ends the run after the current generation
+}
+
+pub struct GenerationReport<'a, Pheno: Phenotype> {
+    pub generation: usize,
+    pub progress: f64, // the progress reported by the EvolutionCoordinator
+    pub fitness: &'a [Fitness<Pheno>], // sorted, the best phenotype comes first
+}
+
+impl<Pheno: Phenotype> GenerationReport<'_, Pheno> {
+    pub fn get_best(&self) -> &Fitness<Pheno> {
+        &self.fitness[0]
+    }
+
+    pub fn get_scores(&self) -> Vec<f64> {
+        self.fitness.iter().map(|fit| fit.score).collect()
+    }
+}
+
+pub trait EvolutionObserver<Pheno: Phenotype> {
+    fn on_start(&mut self) {}
+    fn on_generation(&mut self, report: &GenerationReport<Pheno>) -> ObserverAction;
+    fn on_finish(&mut self, _result: &EvolutionResult<Pheno>) {}
+}
+
+// the observer of the MapElitesLauncher, which has an archive instead of a sorted generation and no
+// EvolutionResult
+pub trait MapElitesObserver<Pheno: Phenotype> {
+    fn on_start(&mut self) {}
+    fn on_generation(
+        &mut self,
+        generation: usize,
+        archive: &MapElitesArchive<Pheno>,
+    ) -> ObserverAction;
+}
+
+// prints the generation with log level 1 and additionally every phenotype with log level 2
+pub struct LogObserver {
+    log_level: usize,
+}
+
+impl LogObserver {
+    pub fn new(log_level: usize) -> Self {
+        Self { log_level }
+    }
+
+    // the multi-objective launcher has no single score, the rank and the scores of every phenotype are
+    // printed instead
+    pub fn on_multi_objective_generation<Pheno: Phenotype>(
+        &mut self,
+        generation: usize,
+        pool: &[MultiObjectiveResult<Pheno>],
+    ) {
+        if self.log_level > 0 {
+            println!("Generation: {}", generation);
+            if self.log_level > 1 {
+                for result in pool.iter() {
+                    println!(
+                        "Rank {} Scores {:?}: Phenotype: {}",
+                        result.rank,
+                        result.scores,
+                        result.winner.to_string_internal()
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<Pheno: Phenotype> EvolutionObserver<Pheno> for LogObserver {
+    fn on_generation(&mut self, report: &GenerationReport<Pheno>) -> ObserverAction {
+        if self.log_level > 0 {
+            println!("Generation: {}", report.generation);
+            if self.log_level > 1 {
+                for fit in report.fitness.iter() {
+                    println!(
+                        "Score {}: Phenotype: {}",
+                        fit.score,
+                        fit.phenotype.to_string_internal()
+                    );
+                }
+            }
+        }
+        ObserverAction::Continue
+    }
+}
+
+// the map elites launcher prints the coverage and the quality diversity score of its archive
+impl<Pheno: Phenotype> MapElitesObserver<Pheno> for LogObserver {
+    fn on_generation(
+        &mut self,
+        generation: usize,
+        archive: &MapElitesArchive<Pheno>,
+    ) -> ObserverAction {
+        if self.log_level > 0 {
+            println!(
+                "Generation: {} Coverage: {} QD score: {}",
+                generation,
+                archive.coverage(),
+                archive.qd_score()
+            );
+            if self.log_level > 1 {
+                for (coordinates, elite) in archive.get_elites() {
+                    println!(
+                        "Cell {:?} Score {}: Phenotype: {}",
+                        coordinates,
+                        elite.score,
+                        elite.phenotype.to_string_internal()
+                    );
+                }
+            }
+        }
+        ObserverAction::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::evol::{
+        evol_launcher::{EvolutionLauncher, EvolutionResult},
+        evol_options::EvolutionOptions,
+        ordinary_evol_strategy::OrdinaryEvolutionStrategy,
+        rand::RandomNumberGenerator,
+        termination::StopReason,
+        test_evol::{XCoordinate, XCoordinateChallenge},
+    };
+
+    use super::{EvolutionObserver, GenerationReport, ObserverAction};
+
+    struct CancelObserver {
+        best_scores: Arc<Mutex<Vec<f64>>>,
+        finished: Arc<Mutex<bool>>,
+    }
+
+    impl EvolutionObserver<XCoordinate> for CancelObserver {
+        fn on_generation(&mut self, report: &GenerationReport<XCoordinate>) -> ObserverAction {
+            self.best_scores
+                .lock()
+                .unwrap()
+                .push(report.get_best().score);
+            if report.generation == 10 {
+                return ObserverAction::Stop;
+            }
+            ObserverAction::Continue
+        }
+
+        fn on_finish(&mut self, _result: &EvolutionResult<XCoordinate>) {
+            *self.finished.lock().unwrap() = true;
+        }
+    }
+
+    #[test]
+    fn test_cancel() {
+        let best_scores = Arc::new(Mutex::new(Vec::new()));
+        let finished = Arc::new(Mutex::new(false));
+        let challenge = XCoordinateChallenge::new(2.0);
+        let mut launcher: EvolutionLauncher<
+            XCoordinate,
+            EvolutionOptions,
+            OrdinaryEvolutionStrategy,
+        > = EvolutionLauncher::new(
+            OrdinaryEvolutionStrategy,
+            Box::new(move |phenotype: &XCoordinate| challenge.score(phenotype)),
+        );
+        launcher.add_observer(Box::new(CancelObserver {
+            best_scores: best_scores.clone(),
+            finished: finished.clone(),
+        }));
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = launcher
+            .evolve(EvolutionOptions::new(), XCoordinate::new(0.0), &mut rng)
+            .unwrap();
+        assert_eq!(result.stop_reason, StopReason::Cancelled);
+        assert_eq!(best_scores.lock().unwrap().len(), 10);
+        assert_eq!(*best_scores.lock().unwrap().last().unwrap(), result.score);
+        assert!(*finished.lock().unwrap());
+    }
+}
@@ -0,0 +1,15 @@
+use std::hash::{Hash, Hasher};
+
+use super::{fitness_cache::FnvHasher, rand::RandomNumberGenerator};
+
+pub mod permutation;
+pub mod real;
+
+// Phenotype::crossover gets no random number generator, so crossovers derive one from both parents;
+// the same parents always give the same child, which keeps seeded runs reproducible
+pub fn crossover_rng<T: Hash + ?Sized>(first: &T, second: &T) -> RandomNumberGenerator {
+    let mut hasher = FnvHasher::default();
+    first.hash(&mut hasher);
+    second.hash(&mut hasher);
+    RandomNumberGenerator::from_seed(hasher.finish())
+}
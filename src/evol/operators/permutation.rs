@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+
+use crate::evol::rand::RandomNumberGenerator;
+
+// every operator expects permutations of 0..len and returns or leaves a permutation of 0..len
+
+// keeps a random segment of first and fills the rest in the order of second, starting after the segment
+pub fn order_crossover(
+    first: &[usize],
+    second: &[usize],
+    rng: &mut RandomNumberGenerator,
+) -> Vec<usize> {
+    let len = first.len();
+    let mut child = first.to_vec();
+    if len < 2 {
+        return child;
+    }
+    let (start, end) = random_segment(len, rng);
+    let mut kept = vec![false; len];
+    for &element in first[start..end].iter() {
+        kept[element] = true;
+    }
+    let mut fill = (0..len)
+        .map(|i| second[(end + i) % len])
+        .filter(|&element| !kept[element]);
+    for i in 0..len - (end - start) {
+        child[(end + i) % len] = fill
+            .next()
+            .expect("second is a permutation of the same length");
+    }
+    child
+}
+
+// keeps a random segment of first and takes the rest from second, following the mapping of the
+// segment wherever an element of second is already taken
+pub fn partially_mapped_crossover(
+    first: &[usize],
+    second: &[usize],
+    rng: &mut RandomNumberGenerator,
+) -> Vec<usize> {
+    let len = first.len();
+    if len < 2 {
+        return first.to_vec();
+    }
+    let (start, end) = random_segment(len, rng);
+    let first_positions = positions(first);
+    let mut in_segment = vec![false; len];
+    for &element in first[start..end].iter() {
+        in_segment[element] = true;
+    }
+    (0..len)
+        .map(|i| {
+            if (start..end).contains(&i) {
+                return first[i];
+            }
+            let mut element = second[i];
+            while in_segment[element] {
+                element = second[first_positions[element]];
+            }
+            element
+        })
+        .collect()
+}
+
+// takes every element from the position it has in one of the parents, alternating between the parents
+// cycle by cycle
+pub fn cycle_crossover(first: &[usize], second: &[usize]) -> Vec<usize> {
+    let len = first.len();
+    let first_positions = positions(first);
+    let mut child = vec![usize::MAX; len];
+    let mut from_first = true;
+    for start in 0..len {
+        if child[start] != usize::MAX {
+            continue;
+        }
+        let mut position = start;
+        loop {
+            child[position] = if from_first {
+                first[position]
+            } else {
+                second[position]
+            };
+            position = first_positions[second[position]];
+            if position == start {
+                break;
+            }
+        }
+        from_first = !from_first;
+    }
+    child
+}
+
+// builds a tour from the edges of both parents, always moving to the neighbour with the fewest
+// remaining edges, so the child inherits as many adjacencies as possible
+pub fn edge_recombination(
+    first: &[usize],
+    second: &[usize],
+    rng: &mut RandomNumberGenerator,
+) -> Vec<usize> {
+    let len = first.len();
+    if len < 2 {
+        return first.to_vec();
+    }
+    let mut neighbours: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); len];
+    for parent in [first, second] {
+        for i in 0..len {
+            let (element, next) = (parent[i], parent[(i + 1) % len]);
+            neighbours[element].insert(next);
+            neighbours[next].insert(element);
+        }
+    }
+    let mut visited = vec![false; len];
+    let mut child = Vec::with_capacity(len);
+    let mut current = first[0];
+    loop {
+        child.push(current);
+        visited[current] = true;
+        if child.len() == len {
+            return child;
+        }
+        for &neighbour in neighbours[current].clone().iter() {
+            neighbours[neighbour].remove(&current);
+        }
+        let candidates: Vec<usize> = neighbours[current].iter().cloned().collect();
+        current = match candidates.iter().map(|&c| neighbours[c].len()).min() {
+            Some(fewest) => {
+                let best: Vec<usize> = candidates
+                    .into_iter()
+                    .filter(|&candidate| neighbours[candidate].len() == fewest)
+                    .collect();
+                best[rng.fetch_index(best.len())]
+            }
+            None => {
+                let unvisited: Vec<usize> = (0..len).filter(|&element| !visited[element]).collect();
+                unvisited[rng.fetch_index(unvisited.len())]
+            }
+        };
+    }
+}
+
+// exchanges two random elements
+pub fn swap_mutation(order: &mut [usize], rng: &mut RandomNumberGenerator) {
+    let len = order.len();
+    if len > 1 {
+        order.swap(rng.fetch_index(len), rng.fetch_index(len));
+    }
+}
+
+// moves a random element to a random position
+pub fn insert_mutation(order: &mut [usize], rng: &mut RandomNumberGenerator) {
+    let len = order.len();
+    if len > 1 {
+        let (from, to) = (rng.fetch_index(len), rng.fetch_index(len));
+        if from < to {
+            order[from..=to].rotate_left(1);
+        } else {
+            order[to..=from].rotate_right(1);
+        }
+    }
+}
+
+// reverses a random segment, for tours this is a 2-opt move
+pub fn inversion_mutation(order: &mut [usize], rng: &mut RandomNumberGenerator) {
+    let len = order.len();
+    if len > 1 {
+        let (start, end) = random_segment(len, rng);
+        order[start..end].reverse();
+    }
+}
+
+// shuffles a random segment
+pub fn scramble_mutation(order: &mut [usize], rng: &mut RandomNumberGenerator) {
+    let len = order.len();
+    if len > 1 {
+        let (start, end) = random_segment(len, rng);
+        for i in (start + 1..end).rev() {
+            order.swap(i, start + rng.fetch_index(i - start + 1));
+        }
+    }
+}
+
+// a non-empty range start..end within 0..len
+fn random_segment(len: usize, rng: &mut RandomNumberGenerator) -> (usize, usize) {
+    let (a, b) = (rng.fetch_index(len), rng.fetch_index(len));
+    (a.min(b), a.max(b) + 1)
+}
+
+// the position of every element
+fn positions(order: &[usize]) -> Vec<usize> {
+    let mut positions = vec![0; order.len()];
+    for (position, &element) in order.iter().enumerate() {
+        positions[element] = position;
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{genomes::permutation::Permutation, rand::RandomNumberGenerator};
+
+    use super::{
+        cycle_crossover, edge_recombination, insert_mutation, inversion_mutation, order_crossover,
+        partially_mapped_crossover, scramble_mutation, swap_mutation,
+    };
+
+    fn is_valid(order: &[usize]) -> bool {
+        Permutation::new(order.to_vec()).is_valid()
+    }
+
+    #[test]
+    fn test_operators_keep_permutations() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        for _ in 0..200 {
+            let first = Permutation::random(12, &mut rng).get_order().to_vec();
+            let second = Permutation::random(12, &mut rng).get_order().to_vec();
+            assert!(is_valid(&order_crossover(&first, &second, &mut rng)));
+            assert!(is_valid(&partially_mapped_crossover(
+                &first, &second, &mut rng
+            )));
+            assert!(is_valid(&cycle_crossover(&first, &second)));
+            assert!(is_valid(&edge_recombination(&first, &second, &mut rng)));
+            let mut order = first.clone();
+            swap_mutation(&mut order, &mut rng);
+            insert_mutation(&mut order, &mut rng);
+            inversion_mutation(&mut order, &mut rng);
+            scramble_mutation(&mut order, &mut rng);
+            assert!(is_valid(&order));
+        }
+    }
+
+    #[test]
+    fn test_cycle_crossover() {
+        let first = [0, 1, 2, 3, 4, 5, 6, 7];
+        let second = [1, 0, 3, 2, 5, 4, 7, 6];
+        // every pair of positions is its own cycle, the cycles alternate between the parents
+        assert_eq!(
+            cycle_crossover(&first, &second),
+            vec![0, 1, 3, 2, 4, 5, 7, 6]
+        );
+    }
+
+    #[test]
+    fn test_edge_recombination_keeps_common_tour() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let first = [0, 1, 2, 3, 4, 5];
+        let second = [3, 4, 5, 0, 1, 2];
+        // both parents are the same tour, so the child must be that tour too
+        let child = edge_recombination(&first, &second, &mut rng);
+        for i in 0..child.len() {
+            let (element, next) = (child[i], child[(i + 1) % child.len()]);
+            assert!((element + 1) % 6 == next || (next + 1) % 6 == element);
+        }
+    }
+}
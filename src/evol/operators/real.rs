@@ -0,0 +1,272 @@
+use std::f64::consts::PI;
+
+use crate::evol::rand::RandomNumberGenerator;
+
+// how a value that left its bounds is brought back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundsHandling {
+    Clip,    // to the nearest bound
+    Reflect, // mirrored at the bounds until it lies within them
+    Wrap,    // re-enters from the opposite bound, values within the bounds stay as they are
+}
+
+impl BoundsHandling {
+    pub fn apply(&self, value: f64, lower: f64, upper: f64) -> f64 {
+        let width = upper - lower;
+        if width <= 0.0 {
+            return lower;
+        }
+        match *self {
+            BoundsHandling::Clip => value.clamp(lower, upper),
+            BoundsHandling::Reflect => {
+                let offset = (value - lower).rem_euclid(2.0 * width);
+                let reflected = if offset > width {
+                    2.0 * width - offset
+                } else {
+                    offset
+                };
+                lower + reflected
+            }
+            // the bounds are inclusive, so upper itself must not wrap around to lower
+            BoundsHandling::Wrap if (lower..=upper).contains(&value) => value,
+            BoundsHandling::Wrap => lower + (value - lower).rem_euclid(width),
+        }
+    }
+}
+
+pub fn apply_bounds(values: &mut [f64], bounds: &[(f64, f64)], handling: BoundsHandling) {
+    for (value, &(lower, upper)) in values.iter_mut().zip(bounds.iter()) {
+        *value = handling.apply(*value, lower, upper);
+    }
+}
+
+// simulated binary crossover, a large distribution index keeps the two children close to their parents
+pub fn sbx_crossover(
+    first: &[f64],
+    second: &[f64],
+    distribution_index: f64,
+    rng: &mut RandomNumberGenerator,
+) -> (Vec<f64>, Vec<f64>) {
+    let exponent = 1.0 / (distribution_index + 1.0);
+    first
+        .iter()
+        .zip(second.iter())
+        .map(|(x1, x2)| {
+            let u = rng.fetch_unit();
+            let beta = if u <= 0.5 {
+                (2.0 * u).powf(exponent)
+            } else {
+                (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+            };
+            (
+                0.5 * ((1.0 + beta) * x1 + (1.0 - beta) * x2),
+                0.5 * ((1.0 - beta) * x1 + (1.0 + beta) * x2),
+            )
+        })
+        .unzip()
+}
+
+// draws every value uniformly from the parents' interval widened by alpha times its length on both sides
+pub fn blx_alpha_crossover(
+    first: &[f64],
+    second: &[f64],
+    alpha: f64,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<f64> {
+    first
+        .iter()
+        .zip(second.iter())
+        .map(|(x1, x2)| {
+            let distance = (x1 - x2).abs();
+            let lower = x1.min(*x2) - alpha * distance;
+            lower + (distance + 2.0 * alpha * distance) * rng.fetch_unit()
+        })
+        .collect()
+}
+
+// num_samples points within the bounds, every bound's interval is split into num_samples strata and every
+// stratum holds exactly one point
+pub fn latin_hypercube(
+    bounds: &[(f64, f64)],
+    num_samples: usize,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Vec<f64>> {
+    let mut samples = vec![Vec::with_capacity(bounds.len()); num_samples];
+    for &(lower, upper) in bounds.iter() {
+        let mut strata: Vec<usize> = (0..num_samples).collect();
+        for i in (1..num_samples).rev() {
+            strata.swap(i, rng.fetch_index(i + 1));
+        }
+        for (sample, stratum) in samples.iter_mut().zip(strata) {
+            let position = (stratum as f64 + rng.fetch_unit()) / num_samples as f64;
+            sample.push(lower + (upper - lower) * position);
+        }
+    }
+    samples
+}
+
+// whole arithmetic crossover, weight 0.5 averages the parents
+pub fn arithmetic_crossover(first: &[f64], second: &[f64], weight: f64) -> Vec<f64> {
+    first
+        .iter()
+        .zip(second.iter())
+        .map(|(x1, x2)| weight * x1 + (1.0 - weight) * x2)
+        .collect()
+}
+
+// Deb's bounded polynomial mutation, every value mutates with mutation_rate and stays within its bounds
+pub fn polynomial_mutation(
+    values: &mut [f64],
+    bounds: &[(f64, f64)],
+    distribution_index: f64,
+    mutation_rate: f64,
+    rng: &mut RandomNumberGenerator,
+) {
+    let exponent = 1.0 / (distribution_index + 1.0);
+    for (value, &(lower, upper)) in values.iter_mut().zip(bounds.iter()) {
+        if upper <= lower || rng.fetch_unit() >= mutation_rate {
+            continue;
+        }
+        let width = upper - lower;
+        let u = rng.fetch_unit();
+        let delta = if u < 0.5 {
+            let distance = 1.0 - (*value - lower) / width;
+            let base = 2.0 * u + (1.0 - 2.0 * u) * distance.powf(distribution_index + 1.0);
+            base.powf(exponent) - 1.0
+        } else {
+            let distance = 1.0 - (upper - *value) / width;
+            let base = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * distance.powf(distribution_index + 1.0);
+            1.0 - base.powf(exponent)
+        };
+        *value = (*value + delta * width).clamp(lower, upper);
+    }
+}
+
+// adds normally distributed noise with standard deviation sigma to every value
+pub fn gaussian_mutation(values: &mut [f64], sigma: f64, rng: &mut RandomNumberGenerator) {
+    for value in values.iter_mut() {
+        *value += sigma * rng.fetch_normal();
+    }
+}
+
+// Schwefel's log-normal self-adaptation: every step size is scaled by a factor shared by all values and
+// one of its own, never drops below min_step_size and then mutates its value with Gaussian noise
+pub fn self_adaptive_mutation(
+    values: &mut [f64],
+    step_sizes: &mut [f64],
+    min_step_size: f64,
+    rng: &mut RandomNumberGenerator,
+) {
+    let n = values.len().max(1) as f64;
+    let global_learning_rate = 1.0 / (2.0 * n).sqrt();
+    let local_learning_rate = 1.0 / (2.0 * n.sqrt()).sqrt();
+    let global = global_learning_rate * rng.fetch_normal();
+    for (value, step_size) in values.iter_mut().zip(step_sizes.iter_mut()) {
+        *step_size = (*step_size * (global + local_learning_rate * rng.fetch_normal()).exp())
+            .max(min_step_size);
+        *value += *step_size * rng.fetch_normal();
+    }
+}
+
+// adds Cauchy distributed noise, its heavy tails occasionally make long jumps out of local optima
+pub fn cauchy_mutation(values: &mut [f64], scale: f64, rng: &mut RandomNumberGenerator) {
+    for value in values.iter_mut() {
+        *value += scale * (PI * (rng.fetch_unit() - 0.5)).tan();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::rand::RandomNumberGenerator;
+
+    use super::{
+        blx_alpha_crossover, cauchy_mutation, gaussian_mutation, latin_hypercube,
+        polynomial_mutation, sbx_crossover, self_adaptive_mutation, BoundsHandling,
+    };
+
+    #[test]
+    fn test_bounds_handling() {
+        assert_eq!(BoundsHandling::Clip.apply(1.5, 0.0, 1.0), 1.0);
+        assert!((BoundsHandling::Reflect.apply(1.25, 0.0, 1.0) - 0.75).abs() < 1e-12);
+        assert!((BoundsHandling::Reflect.apply(-0.25, 0.0, 1.0) - 0.25).abs() < 1e-12);
+        assert!((BoundsHandling::Reflect.apply(2.25, 0.0, 1.0) - 0.25).abs() < 1e-12);
+        assert!((BoundsHandling::Wrap.apply(1.25, 0.0, 1.0) - 0.25).abs() < 1e-12);
+        assert!((BoundsHandling::Wrap.apply(-0.25, 0.0, 1.0) - 0.75).abs() < 1e-12);
+        for handling in [
+            BoundsHandling::Clip,
+            BoundsHandling::Reflect,
+            BoundsHandling::Wrap,
+        ] {
+            assert_eq!(handling.apply(0.0, 0.0, 1.0), 0.0);
+            assert_eq!(handling.apply(1.0, 0.0, 1.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_crossovers() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let (first, second) = (vec![0.0, 1.0, -3.0], vec![2.0, 1.0, 5.0]);
+        for _ in 0..100 {
+            // SBX keeps the mean of the parents
+            let (child_1, child_2) = sbx_crossover(&first, &second, 15.0, &mut rng);
+            for i in 0..first.len() {
+                assert!((child_1[i] + child_2[i] - first[i] - second[i]).abs() < 1e-9);
+            }
+            let child = blx_alpha_crossover(&first, &second, 0.5, &mut rng);
+            assert!((-1.0..=3.0).contains(&child[0]));
+            assert_eq!(child[1], 1.0);
+            assert!((-7.0..=9.0).contains(&child[2]));
+        }
+    }
+
+    #[test]
+    fn test_latin_hypercube() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let bounds = [(0.0, 1.0), (-10.0, 10.0)];
+        let samples = latin_hypercube(&bounds, 8, &mut rng);
+        assert_eq!(samples.len(), 8);
+        for (dimension, &(lower, upper)) in bounds.iter().enumerate() {
+            let mut strata: Vec<usize> = samples
+                .iter()
+                .map(|sample| ((sample[dimension] - lower) / (upper - lower) * 8.0) as usize)
+                .collect();
+            strata.sort();
+            assert_eq!(strata, (0..8).collect::<Vec<usize>>());
+        }
+    }
+
+    #[test]
+    fn test_mutations() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let bounds = vec![(0.0, 1.0); 4];
+        for _ in 0..100 {
+            let mut values = vec![0.0, 0.01, 0.5, 1.0];
+            polynomial_mutation(&mut values, &bounds, 20.0, 1.0, &mut rng);
+            assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+        }
+        let mut values = vec![0.0; 10000];
+        gaussian_mutation(&mut values, 2.0, &mut rng);
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|value| value * value).sum::<f64>() / values.len() as f64;
+        assert!(mean.abs() < 0.1);
+        assert!((variance.sqrt() - 2.0).abs() < 0.1);
+        // half of the Cauchy noise lies within the scale, and its tails reach far beyond
+        let mut values = vec![0.0; 10000];
+        cauchy_mutation(&mut values, 2.0, &mut rng);
+        let num_within_scale = values.iter().filter(|value| value.abs() < 2.0).count();
+        assert!((num_within_scale as f64 / values.len() as f64 - 0.5).abs() < 0.02);
+        assert!(values.iter().any(|value| value.abs() > 200.0));
+    }
+
+    #[test]
+    fn test_self_adaptive_mutation() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let mut values = vec![0.0; 3];
+        let mut step_sizes = vec![1.0, 1e-3, 1e-6];
+        for _ in 0..100 {
+            self_adaptive_mutation(&mut values, &mut step_sizes, 1e-4, &mut rng);
+            assert!(step_sizes.iter().all(|step_size| *step_size >= 1e-4));
+        }
+        assert!(values.iter().all(|value| value.is_finite()));
+    }
+}
@@ -1,5 +1,7 @@
 use super::{
+    error::EvolutionError,
     evol_coordinator::EvolutionCoordinator,
+    generation_model::GenerationModel,
     rand::RandomNumberGenerator,
     traits::{EvolutionOptionsTrait, EvolutionStrategy, Phenotype},
 };
@@ -17,21 +19,37 @@ where
         rng: &mut RandomNumberGenerator,
         evol_coordinator: EvolutionCoordinator,
         evol_options: &EvolOptions,
-    ) -> Vec<Pheno> {
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        if parents.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        let winner_previous_generation = &parents[0];
+        // a steady state population keeps its best member, all of its num_children offspring are bred
+        if evol_options.get_generation_model() != GenerationModel::Generational {
+            return Ok((0..evol_options.get_num_children())
+                .map(|k| {
+                    let mut child = winner_previous_generation.clone();
+                    if parents.len() > 1 {
+                        child.crossover(&parents[1 + k % (parents.len() - 1)]);
+                    }
+                    child.mutate(rng, evol_coordinator);
+                    child
+                })
+                .collect());
+        }
         let mut children: Vec<Pheno> = Vec::new();
-        let winner_previous_generation = parents[0];
-        children.push(winner_previous_generation);
-        for i in 1..parents.len() {
-            let mut child = winner_previous_generation;
-            child.crossover(&parents[i]);
-            child.mutate(rng, evol_coordinator.clone());
+        children.push(winner_previous_generation.clone());
+        for parent in parents.iter().skip(1) {
+            let mut child = winner_previous_generation.clone();
+            child.crossover(parent);
+            child.mutate(rng, evol_coordinator);
             children.push(child);
         }
         for _ in parents.len()..evol_options.get_num_children() {
-            let mut child = winner_previous_generation;
-            child.mutate(rng, evol_coordinator.clone());
+            let mut child = winner_previous_generation.clone();
+            child.mutate(rng, evol_coordinator);
             children.push(child);
         }
-        children.clone()
+        Ok(children)
     }
 }
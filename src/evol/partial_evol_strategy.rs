@@ -1,5 +1,7 @@
 use super::{
+    error::EvolutionError,
     evol_coordinator::EvolutionCoordinator,
+    generation_model::GenerationModel,
     rand::RandomNumberGenerator,
     traits::{EvolutionStrategy, PartialEvolutionOptionsTrait, PartialPhenotype},
 };
@@ -17,52 +19,134 @@ where
         rng: &mut RandomNumberGenerator,
         evol_coordinator: EvolutionCoordinator,
         evol_options: &EvolOptions,
-    ) -> Vec<Pheno> {
-        let mut develop = |pheno: Pheno, initial_mutate: bool| -> Option<Pheno> {
-            let mut phenotype = pheno;
-            if initial_mutate {
-                phenotype.mutate(rng, evol_coordinator);
-            }
-            let pheno_type_in_range = |phenotype: &Pheno| -> bool {
-                let magnitude = phenotype.magnitude();
-                let min_magnitude = evol_options.get_min_magnitude();
-                let max_magnitude = evol_options.get_max_magnitude();
-                magnitude >= min_magnitude && magnitude <= max_magnitude
-            };
-            let mut advance_phenotype_n_times = |phenotype: Pheno, n: usize| -> Pheno {
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        if parents.is_empty() {
+            return Err(EvolutionError::EmptyPopulation);
+        }
+        let min_magnitude = evol_options.get_min_magnitude();
+        let max_magnitude = evol_options.get_max_magnitude();
+        let max_attempts = evol_options.get_max_attempts();
+        let in_range = |phenotype: &Pheno| -> bool {
+            let magnitude = phenotype.magnitude();
+            magnitude >= min_magnitude && magnitude <= max_magnitude
+        };
+        // mutates until the phenotype is in range, every attempt restarts from the same phenotype
+        let mut develop =
+            |phenotype: Pheno, initial_mutate: bool| -> Result<Pheno, EvolutionError> {
                 let mut phenotype = phenotype;
-                for _ in 0..n {
+                if initial_mutate {
                     phenotype.mutate(rng, evol_coordinator);
-                    if pheno_type_in_range(&phenotype) {
-                        break;
+                }
+                if in_range(&phenotype) {
+                    return Ok(phenotype);
+                }
+                for _ in 0..max_attempts {
+                    let mut new_phenotype = phenotype.clone();
+                    for _ in 0..evol_options.get_max_mutations_per_attempt() {
+                        new_phenotype.mutate(rng, evol_coordinator);
+                        if in_range(&new_phenotype) {
+                            return Ok(new_phenotype);
+                        }
                     }
                 }
-                phenotype
+                Err(EvolutionError::InfeasibleRegion {
+                    min_magnitude,
+                    max_magnitude,
+                    num_attempts: max_attempts,
+                })
             };
-            let mut new_phenotype = phenotype.clone();
-            for _ in 0..1000 {
-                if pheno_type_in_range(&new_phenotype) {
-                    return Some(new_phenotype);
-                }
-                new_phenotype = advance_phenotype_n_times(phenotype.clone(), 1000);
-            }
-            None
-        };
 
+        let winner_previous_generation = &parents[0];
+        // a steady state population keeps its best member, all of its num_children offspring are bred
+        if evol_options.get_generation_model() != GenerationModel::Generational {
+            return (0..evol_options.get_num_children())
+                .map(|k| {
+                    let mut child = winner_previous_generation.clone();
+                    if parents.len() > 1 {
+                        child.crossover(&parents[1 + k % (parents.len() - 1)]);
+                    }
+                    develop(child, true)
+                })
+                .collect();
+        }
         let mut children: Vec<Pheno> = Vec::new();
-        let winner_previous_generation = parents[0];
-        children.push(develop(winner_previous_generation, false).unwrap());
-        for i in 1..parents.len() {
-            let mut child = winner_previous_generation;
-            child.crossover(&parents[i]);
-            let mutated_child = develop(child, true).unwrap();
-            children.push(mutated_child);
+        children.push(develop(winner_previous_generation.clone(), false)?);
+        for parent in parents.iter().skip(1) {
+            let mut child = winner_previous_generation.clone();
+            child.crossover(parent);
+            children.push(develop(child, true)?);
         }
         for _ in parents.len()..evol_options.get_num_children() {
-            let child = winner_previous_generation;
-            let mutated_child = develop(child, true).unwrap();
-            children.push(mutated_child);
+            let child = winner_previous_generation.clone();
+            children.push(develop(child, true)?);
         }
-        children.clone()
+        Ok(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        error::EvolutionError,
+        evol_coordinator::EvolutionCoordinator,
+        evol_options::{EvolutionOptions, PartialEvolutionOptions},
+        rand::RandomNumberGenerator,
+        test_evol::XCoordinate,
+        traits::{EvolutionStrategy, PartialPhenotype},
+    };
+
+    use super::PartialEvolutionStrategy;
+
+    #[test]
+    fn test_infeasible_region() {
+        // mutations move by at most 1 per step, so 100 is out of reach of 10 mutations
+        let evol_options = PartialEvolutionOptions::new(EvolutionOptions::new(), 100.0, 101.0)
+            .with_retry_budget(10, 10);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = PartialEvolutionStrategy.breed(
+            vec![XCoordinate::new(0.0)],
+            &mut rng,
+            EvolutionCoordinator::new(&evol_options),
+            &evol_options,
+        );
+        assert!(matches!(
+            result,
+            Err(EvolutionError::InfeasibleRegion {
+                num_attempts: 10,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_last_attempt() {
+        // a single attempt has to be enough when its mutations reach the range
+        let evol_options = PartialEvolutionOptions::new(EvolutionOptions::new(), 0.5, 100.0)
+            .with_retry_budget(1, 1000);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let children = PartialEvolutionStrategy
+            .breed(
+                vec![XCoordinate::new(0.0)],
+                &mut rng,
+                EvolutionCoordinator::new(&evol_options),
+                &evol_options,
+            )
+            .unwrap();
+        assert!(children
+            .iter()
+            .all(|child| (0.5..=100.0).contains(&child.magnitude())));
+    }
+
+    #[test]
+    fn test_no_parents() {
+        let evol_options = PartialEvolutionOptions::new(EvolutionOptions::new(), 0.0, 1.0);
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let result = PartialEvolutionStrategy.breed(
+            Vec::<XCoordinate>::new(),
+            &mut rng,
+            EvolutionCoordinator::new(&evol_options),
+            &evol_options,
+        );
+        assert!(matches!(result, Err(EvolutionError::EmptyPopulation)));
     }
 }
@@ -1,29 +1,179 @@
 use std::collections::VecDeque;
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::{Error, Rng, RngCore};
 
+use super::checkpoint::Checkpointable;
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_DEFAULT_STREAM: u64 = 1442695040888963407;
+
+// PCG-XSH-RR 32 bit generator, the whole state is two integers so runs can be reproduced from the seed
+// and the stream; it replaces the former public ThreadRng field rd, which could not be seeded, and
+// implements RngCore so that rand's Rng methods like gen_range are called on the generator itself
+#[derive(Clone)]
 pub struct RandomNumberGenerator {
-    pub rd: ThreadRng,
+    seed: u64,
+    state: u64,
+    increment: u64,
 }
 
 impl RandomNumberGenerator {
     pub fn new() -> RandomNumberGenerator {
-        RandomNumberGenerator {
-            rd: rand::thread_rng(),
-        }
+        RandomNumberGenerator::from_seed(rand::thread_rng().gen())
     }
 
     pub fn from_seed(seed: u64) -> RandomNumberGenerator {
-        RandomNumberGenerator {
-            rd: rand::thread_rng(),
-        }
+        RandomNumberGenerator::from_seed_and_stream(seed, PCG_DEFAULT_STREAM)
+    }
+
+    // generators with the same seed but different streams produce independent sequences
+    pub fn from_seed_and_stream(seed: u64, stream: u64) -> RandomNumberGenerator {
+        let mut rng = RandomNumberGenerator {
+            seed,
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    // a run is reproduced by from_seed_and_stream with the seed and this stream
+    pub fn get_stream(&self) -> u64 {
+        self.increment >> 1
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.state = old_state
+            .wrapping_mul(PCG_MULTIPLIER)
+            .wrapping_add(self.increment);
+        let xor_shifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rotation = (old_state >> 59) as u32;
+        xor_shifted.rotate_right(rotation)
     }
 
+    // uniformly distributed numbers in from..to
     pub fn fetch_uniform(&mut self, from: f32, to: f32, num: usize) -> VecDeque<f32> {
         let mut uniform_numbers = VecDeque::new();
         for _ in 0..num {
-            uniform_numbers.push_back(self.rd.gen_range(from..to));
+            // 24 random bits fill the mantissa of a unit in [0, 1), the range is scaled in f64
+            let unit = (self.next_u32() >> 8) as f64 / (1u32 << 24) as f64;
+            let number = (from as f64 + (to as f64 - from as f64) * unit) as f32;
+            // rounding to f32 can reach to, which the half-open range excludes
+            if number >= to && from < to {
+                uniform_numbers.push_back(to.next_down().max(from));
+            } else {
+                uniform_numbers.push_back(number);
+            }
         }
         uniform_numbers
     }
+
+    // uniformly distributed f64 in [0, 1) built from 53 random bits
+    pub fn fetch_unit(&mut self) -> f64 {
+        let bits = ((self.next_u32() as u64) << 21) ^ ((self.next_u32() as u64) >> 11);
+        bits as f64 / (1u64 << 53) as f64
+    }
+
+    // standard normally distributed f64 from the Box-Muller transform
+    pub fn fetch_normal(&mut self) -> f64 {
+        // 1 - unit lies in (0, 1] so the logarithm stays finite
+        let radius = (-2.0 * (1.0 - self.fetch_unit()).ln()).sqrt();
+        radius * (2.0 * std::f64::consts::PI * self.fetch_unit()).cos()
+    }
+
+    // uniformly distributed index in 0..len
+    pub fn fetch_index(&mut self, len: usize) -> usize {
+        ((self.next_u32() as u64 * len as u64) >> 32) as usize
+    }
+}
+
+impl RngCore for RandomNumberGenerator {
+    fn next_u32(&mut self) -> u32 {
+        RandomNumberGenerator::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl Checkpointable for RandomNumberGenerator {
+    fn to_checkpoint(&self) -> String {
+        format!("{} {} {}", self.seed, self.state, self.increment)
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let mut numbers = line.split(' ').map(|number| number.parse::<u64>());
+        Some(RandomNumberGenerator {
+            seed: numbers.next()?.ok()?,
+            state: numbers.next()?.ok()?,
+            increment: numbers.next()?.ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::RandomNumberGenerator;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let mut first = RandomNumberGenerator::from_seed(42);
+        let mut second = RandomNumberGenerator::from_seed(42);
+        assert_eq!(
+            first.fetch_uniform(-1.0, 1.0, 100),
+            second.fetch_uniform(-1.0, 1.0, 100)
+        );
+        assert_eq!(first.get_seed(), 42);
+    }
+
+    #[test]
+    fn test_uniform_range() {
+        let mut rng = RandomNumberGenerator::from_seed(7);
+        for number in rng.fetch_uniform(2.0, 3.0, 1000) {
+            assert!((2.0..3.0).contains(&number));
+        }
+        // in a range of one f32 step half the numbers round up to its end
+        let to = 2.0f32.next_up();
+        for number in rng.fetch_uniform(2.0, to, 1000) {
+            assert_eq!(number, 2.0);
+        }
+        assert_eq!(rng.fetch_uniform(1.0, 1.0, 1), vec![1.0]);
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut rng = RandomNumberGenerator::from_seed_and_stream(3, 5);
+        let mut reproduced =
+            RandomNumberGenerator::from_seed_and_stream(rng.get_seed(), rng.get_stream());
+        assert_eq!(rng.next_u32(), reproduced.next_u32());
+        assert_eq!(
+            RandomNumberGenerator::from_seed(3).get_stream(),
+            1442695040888963407
+        );
+        // rand's Rng methods work on the generator
+        let number: f64 = rng.gen_range(0.0..1.0);
+        assert!((0.0..1.0).contains(&number));
+    }
 }
@@ -0,0 +1,76 @@
+use super::checkpoint::Checkpointable;
+
+// what happens to NaN and infinite scores before the candidates are ranked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NonFiniteScorePolicy {
+    Reject,     // any non finite score ends the run with an error
+    NanAsWorst, // NaN ranks below every other score, infinities are kept
+    Clamp,      // NaN becomes the lowest finite score, infinities the nearest finite one
+}
+
+impl NonFiniteScorePolicy {
+    // the score to rank the candidate with, None if the score is rejected
+    pub fn apply(&self, score: f64) -> Option<f64> {
+        if score.is_finite() {
+            return Some(score);
+        }
+        match *self {
+            NonFiniteScorePolicy::Reject => None,
+            NonFiniteScorePolicy::NanAsWorst if score.is_nan() => Some(f64::NEG_INFINITY),
+            NonFiniteScorePolicy::NanAsWorst => Some(score),
+            NonFiniteScorePolicy::Clamp if score.is_nan() => Some(f64::MIN),
+            NonFiniteScorePolicy::Clamp => Some(score.clamp(f64::MIN, f64::MAX)),
+        }
+    }
+}
+
+impl Checkpointable for NonFiniteScorePolicy {
+    fn to_checkpoint(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        match line {
+            "Reject" => Some(NonFiniteScorePolicy::Reject),
+            "NanAsWorst" => Some(NonFiniteScorePolicy::NanAsWorst),
+            "Clamp" => Some(NonFiniteScorePolicy::Clamp),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NonFiniteScorePolicy;
+
+    #[test]
+    fn test_apply() {
+        let policies = [
+            NonFiniteScorePolicy::Reject,
+            NonFiniteScorePolicy::NanAsWorst,
+            NonFiniteScorePolicy::Clamp,
+        ];
+        for policy in policies {
+            assert_eq!(policy.apply(1.5), Some(1.5));
+        }
+        assert_eq!(NonFiniteScorePolicy::Reject.apply(f64::NAN), None);
+        assert_eq!(NonFiniteScorePolicy::Reject.apply(f64::INFINITY), None);
+        assert_eq!(
+            NonFiniteScorePolicy::NanAsWorst.apply(f64::NAN),
+            Some(f64::NEG_INFINITY)
+        );
+        assert_eq!(
+            NonFiniteScorePolicy::NanAsWorst.apply(f64::INFINITY),
+            Some(f64::INFINITY)
+        );
+        assert_eq!(NonFiniteScorePolicy::Clamp.apply(f64::NAN), Some(f64::MIN));
+        assert_eq!(
+            NonFiniteScorePolicy::Clamp.apply(f64::INFINITY),
+            Some(f64::MAX)
+        );
+        assert_eq!(
+            NonFiniteScorePolicy::Clamp.apply(f64::NEG_INFINITY),
+            Some(f64::MIN)
+        );
+    }
+}
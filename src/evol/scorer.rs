@@ -0,0 +1,262 @@
+use std::{
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+pub type ScoreFn<Pheno, Score> = dyn Fn(&Pheno) -> Score;
+pub type SyncScoreFn<Pheno, Score> = dyn Fn(&Pheno) -> Score + Send + Sync;
+
+// evaluates the score function of a launcher, the results always come in the order of the candidates
+pub trait Scorer<Pheno, Score> {
+    fn score(&self, candidates: &[&Pheno], num_threads: usize) -> Vec<Score>;
+
+    // evaluates another function of the candidates the way the scores are evaluated, like the behaviour
+    // descriptors of a novelty search
+    fn evaluate<Value: Send + 'static>(
+        &self,
+        candidates: &[&Pheno],
+        num_threads: usize,
+        value_fn: &Arc<SyncScoreFn<Pheno, Value>>,
+    ) -> Vec<Value>;
+}
+
+// scores on the calling thread and ignores num_threads, neither the phenotype nor the score function
+// has to be Send or Sync
+pub struct SerialScorer<Pheno, Score> {
+    score_fn: Box<ScoreFn<Pheno, Score>>,
+}
+
+impl<Pheno, Score> SerialScorer<Pheno, Score> {
+    pub fn new(score_fn: Box<ScoreFn<Pheno, Score>>) -> Self {
+        Self { score_fn }
+    }
+}
+
+impl<Pheno, Score> Scorer<Pheno, Score> for SerialScorer<Pheno, Score> {
+    fn score(&self, candidates: &[&Pheno], _num_threads: usize) -> Vec<Score> {
+        candidates
+            .iter()
+            .map(|candidate| (self.score_fn)(candidate))
+            .collect()
+    }
+
+    fn evaluate<Value: Send + 'static>(
+        &self,
+        candidates: &[&Pheno],
+        _num_threads: usize,
+        value_fn: &Arc<SyncScoreFn<Pheno, Value>>,
+    ) -> Vec<Value> {
+        candidates
+            .iter()
+            .map(|candidate| value_fn(candidate))
+            .collect()
+    }
+}
+
+// scores on num_threads worker threads, which are spawned on first use and kept for later generations
+// and runs until num_threads changes; the candidates are cloned to the workers
+pub struct ParallelScorer<Pheno, Score> {
+    score_fn: Arc<SyncScoreFn<Pheno, Score>>,
+    pool: RefCell<Option<WorkerPool>>,
+}
+
+impl<Pheno, Score> ParallelScorer<Pheno, Score>
+where
+    Pheno: Clone + Send + 'static,
+    Score: Send + 'static,
+{
+    pub fn new(score_fn: Box<SyncScoreFn<Pheno, Score>>) -> Self {
+        Self {
+            score_fn: Arc::from(score_fn),
+            pool: RefCell::new(None),
+        }
+    }
+
+    fn map<Value: Send + 'static>(
+        &self,
+        candidates: &[&Pheno],
+        num_threads: usize,
+        value_fn: &Arc<SyncScoreFn<Pheno, Value>>,
+    ) -> Vec<Value> {
+        if num_threads <= 1 || candidates.len() <= 1 {
+            return candidates
+                .iter()
+                .map(|candidate| value_fn(candidate))
+                .collect();
+        }
+        let mut pool = self.pool.borrow_mut();
+        if pool
+            .as_ref()
+            .is_none_or(|pool| pool.get_num_threads() != num_threads)
+        {
+            *pool = Some(WorkerPool::new(num_threads));
+        }
+        pool.as_ref()
+            .expect("the pool was just spawned")
+            .map(candidates, value_fn)
+    }
+}
+
+impl<Pheno, Score> Scorer<Pheno, Score> for ParallelScorer<Pheno, Score>
+where
+    Pheno: Clone + Send + 'static,
+    Score: Send + 'static,
+{
+    fn score(&self, candidates: &[&Pheno], num_threads: usize) -> Vec<Score> {
+        self.map(candidates, num_threads, &self.score_fn)
+    }
+
+    fn evaluate<Value: Send + 'static>(
+        &self,
+        candidates: &[&Pheno],
+        num_threads: usize,
+        value_fn: &Arc<SyncScoreFn<Pheno, Value>>,
+    ) -> Vec<Value> {
+        self.map(candidates, num_threads, value_fn)
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// worker threads that take jobs from a shared queue until the pool is dropped
+struct WorkerPool {
+    jobs: Option<mpsc::Sender<Job>>, // only None while the pool is dropped
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    fn new(num_threads: usize) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        let workers = (0..num_threads)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || loop {
+                    // the lock is released before the job runs
+                    let job = queue.lock().expect("no job runs under the lock").recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            jobs: Some(jobs),
+            workers,
+        }
+    }
+
+    fn get_num_threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    // a panic of value_fn is caught on the worker and resumed on the calling thread once every job ended
+    fn map<Pheno, Value>(
+        &self,
+        candidates: &[&Pheno],
+        value_fn: &Arc<SyncScoreFn<Pheno, Value>>,
+    ) -> Vec<Value>
+    where
+        Pheno: Clone + Send + 'static,
+        Value: Send + 'static,
+    {
+        let jobs = self.jobs.as_ref().expect("the pool is not dropped");
+        let (results, received) = mpsc::channel();
+        for (index, &candidate) in candidates.iter().enumerate() {
+            let candidate = candidate.clone();
+            let value_fn = Arc::clone(value_fn);
+            let results = results.clone();
+            jobs.send(Box::new(move || {
+                let value = panic::catch_unwind(AssertUnwindSafe(|| value_fn(&candidate)));
+                // the receiver outlives every job of its batch
+                let _ = results.send((index, value));
+            }))
+            .expect("the workers run as long as the pool");
+        }
+        // the iteration ends when the last job dropped its sender
+        drop(results);
+        let mut values: Vec<Option<Value>> = (0..candidates.len()).map(|_| None).collect();
+        let mut panic_payload = None;
+        for (index, value) in received.iter() {
+            match value {
+                Ok(value) => values[index] = Some(value),
+                Err(payload) => {
+                    panic_payload.get_or_insert(payload);
+                }
+            }
+        }
+        if let Some(payload) = panic_payload {
+            panic::resume_unwind(payload);
+        }
+        values
+            .into_iter()
+            .map(|value| value.expect("every job sent its value"))
+            .collect()
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // closing the queue ends the workers' loops
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use super::{ParallelScorer, Scorer, SerialScorer};
+
+    #[test]
+    fn test_same_order() {
+        let candidates: Vec<u64> = (0..100).collect();
+        let refs: Vec<&u64> = candidates.iter().collect();
+        let serial = SerialScorer::new(Box::new(|x: &u64| x * x));
+        let parallel = ParallelScorer::new(Box::new(|x: &u64| x * x));
+        assert_eq!(serial.score(&refs, 4), parallel.score(&refs, 4));
+        let value_fn = Arc::new(|x: &u64| vec![*x as f64]);
+        assert_eq!(
+            serial.evaluate(&refs, 4, &(value_fn.clone() as Arc<_>)),
+            parallel.evaluate(&refs, 4, &(value_fn as Arc<_>))
+        );
+    }
+
+    #[test]
+    fn test_workers_are_kept() {
+        let thread_ids = Arc::new(Mutex::new(HashSet::new()));
+        let recorded_ids = Arc::clone(&thread_ids);
+        let parallel = ParallelScorer::new(Box::new(move |x: &u64| {
+            recorded_ids.lock().unwrap().insert(thread::current().id());
+            *x
+        }));
+        let candidates: Vec<u64> = (0..20).collect();
+        let refs: Vec<&u64> = candidates.iter().collect();
+        for _ in 0..10 {
+            parallel.score(&refs, 3);
+        }
+        assert!(thread_ids.lock().unwrap().len() <= 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "odd candidate")]
+    fn test_panic_is_resumed() {
+        let parallel = ParallelScorer::new(Box::new(|x: &u64| {
+            assert!(x.is_multiple_of(2), "odd candidate");
+            *x
+        }));
+        let candidates: Vec<u64> = (0..4).collect();
+        let refs: Vec<&u64> = candidates.iter().collect();
+        parallel.score(&refs, 2);
+    }
+}
@@ -0,0 +1,332 @@
+use super::{checkpoint::Checkpointable, rand::RandomNumberGenerator};
+
+pub trait SelectionOperator {
+    // scores are sorted descending, the selected indices are returned in ascending order
+    // so that the best selected parent always comes first
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<usize>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    Truncation,
+    Tournament(usize), // the number of contestants per tournament
+    RouletteWheel,
+    StochasticUniversalSampling,
+    LinearRanking(f64), // the selection pressure between 1.0 and 2.0
+}
+
+impl SelectionOperator for Selection {
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<usize> {
+        match *self {
+            Selection::Truncation => TruncationSelection.select(scores, num_parents, rng),
+            Selection::Tournament(tournament_size) => {
+                TournamentSelection::new(tournament_size).select(scores, num_parents, rng)
+            }
+            Selection::RouletteWheel => RouletteWheelSelection.select(scores, num_parents, rng),
+            Selection::StochasticUniversalSampling => {
+                StochasticUniversalSampling.select(scores, num_parents, rng)
+            }
+            Selection::LinearRanking(selection_pressure) => {
+                LinearRankingSelection::new(selection_pressure).select(scores, num_parents, rng)
+            }
+        }
+    }
+}
+
+impl Checkpointable for Selection {
+    fn to_checkpoint(&self) -> String {
+        match *self {
+            Selection::Truncation => "Truncation".to_string(),
+            Selection::Tournament(tournament_size) => format!("Tournament:{}", tournament_size),
+            Selection::RouletteWheel => "RouletteWheel".to_string(),
+            Selection::StochasticUniversalSampling => "StochasticUniversalSampling".to_string(),
+            Selection::LinearRanking(selection_pressure) => {
+                format!("LinearRanking:{}", selection_pressure)
+            }
+        }
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        match line.split_once(':') {
+            Some(("Tournament", tournament_size)) => {
+                Some(Selection::Tournament(tournament_size.parse().ok()?))
+            }
+            Some(("LinearRanking", selection_pressure)) => {
+                Some(Selection::LinearRanking(selection_pressure.parse().ok()?))
+            }
+            Some(_) => None,
+            None => match line {
+                "Truncation" => Some(Selection::Truncation),
+                "RouletteWheel" => Some(Selection::RouletteWheel),
+                "StochasticUniversalSampling" => Some(Selection::StochasticUniversalSampling),
+                _ => None,
+            },
+        }
+    }
+}
+
+pub struct TruncationSelection;
+
+impl SelectionOperator for TruncationSelection {
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        _: &mut RandomNumberGenerator,
+    ) -> Vec<usize> {
+        (0..num_parents.min(scores.len())).collect()
+    }
+}
+
+pub struct TournamentSelection {
+    tournament_size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(tournament_size: usize) -> Self {
+        Self {
+            tournament_size: tournament_size.max(1),
+        }
+    }
+}
+
+impl SelectionOperator for TournamentSelection {
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<usize> {
+        if scores.is_empty() {
+            return Vec::new();
+        }
+        let mut selected: Vec<usize> = (0..num_parents)
+            .map(|_| {
+                // the scores are sorted, so the smallest index wins the tournament
+                (0..self.tournament_size)
+                    .map(|_| rng.fetch_index(scores.len()))
+                    .min()
+                    .unwrap()
+            })
+            .collect();
+        selected.sort();
+        selected
+    }
+}
+
+pub struct RouletteWheelSelection;
+
+impl SelectionOperator for RouletteWheelSelection {
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<usize> {
+        if scores.is_empty() {
+            return Vec::new();
+        }
+        let weights = shifted_weights(scores);
+        let total: f64 = weights.iter().sum();
+        let mut selected: Vec<usize> = (0..num_parents)
+            .map(|_| spin(&weights, rng.fetch_unit() * total))
+            .collect();
+        selected.sort();
+        selected
+    }
+}
+
+pub struct StochasticUniversalSampling;
+
+impl SelectionOperator for StochasticUniversalSampling {
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<usize> {
+        if num_parents == 0 || scores.is_empty() {
+            return Vec::new();
+        }
+        let weights = shifted_weights(scores);
+        let total: f64 = weights.iter().sum();
+        let distance = total / num_parents as f64;
+        let start = rng.fetch_unit() * distance;
+        // the pointers are ascending, so are the selected indices
+        (0..num_parents)
+            .map(|i| spin(&weights, start + i as f64 * distance))
+            .collect()
+    }
+}
+
+pub struct LinearRankingSelection {
+    selection_pressure: f64,
+}
+
+impl LinearRankingSelection {
+    pub fn new(selection_pressure: f64) -> Self {
+        Self {
+            selection_pressure: selection_pressure.clamp(1.0, 2.0),
+        }
+    }
+}
+
+impl SelectionOperator for LinearRankingSelection {
+    fn select(
+        &self,
+        scores: &[f64],
+        num_parents: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<usize> {
+        let n = scores.len();
+        if n < 2 {
+            return vec![0; num_parents.min(n)];
+        }
+        // rank 0 is the best phenotype and receives the weight of the selection pressure
+        let weights: Vec<f64> = (0..n)
+            .map(|rank| {
+                self.selection_pressure
+                    - 2.0 * (self.selection_pressure - 1.0) * rank as f64 / (n - 1) as f64
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        let mut selected: Vec<usize> = (0..num_parents)
+            .map(|_| spin(&weights, rng.fetch_unit() * total))
+            .collect();
+        selected.sort();
+        selected
+    }
+}
+
+// fitness proportional selection needs finite non negative weights: the scores are shifted by the worst
+// finite one if it is negative and scaled to at most 1, so that their sum can not overflow; infinite best
+// scores share all of the weight and infinite worst scores get none
+fn shifted_weights(scores: &[f64]) -> Vec<f64> {
+    if scores.contains(&f64::INFINITY) {
+        return scores
+            .iter()
+            .map(|&score| if score == f64::INFINITY { 1.0 } else { 0.0 })
+            .collect();
+    }
+    let finite_scores = scores.iter().cloned().filter(|score| score.is_finite());
+    let worst = finite_scores.clone().fold(0.0, f64::min);
+    let best = finite_scores.fold(f64::NEG_INFINITY, f64::max);
+    // halved, so that the difference of the extreme finite scores does not overflow
+    let range = best / 2.0 - worst / 2.0;
+    let weights: Vec<f64> = scores
+        .iter()
+        .map(|&score| {
+            if !score.is_finite() {
+                0.0
+            } else if range > 0.0 {
+                (score / 2.0 - worst / 2.0) / range
+            } else {
+                1.0
+            }
+        })
+        .collect();
+    if weights.iter().sum::<f64>() > 0.0 {
+        weights
+    } else {
+        vec![1.0; scores.len()]
+    }
+}
+
+// the weights must not be empty
+fn spin(weights: &[f64], pointer: f64) -> usize {
+    let mut cumulative = 0.0;
+    for (i, weight) in weights.iter().enumerate() {
+        cumulative += weight;
+        if pointer < cumulative {
+            return i;
+        }
+    }
+    weights.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::rand::RandomNumberGenerator;
+
+    use super::{Selection, SelectionOperator};
+
+    #[test]
+    fn test_selections() {
+        let mut rng = RandomNumberGenerator::from_seed(3);
+        let scores = vec![10.0, 8.0, 5.0, 1.0, -2.0];
+        let selections = [
+            Selection::Truncation,
+            Selection::Tournament(3),
+            Selection::RouletteWheel,
+            Selection::StochasticUniversalSampling,
+            Selection::LinearRanking(1.5),
+        ];
+        for selection in selections.iter() {
+            let selected = selection.select(&scores, 3, &mut rng);
+            assert_eq!(selected.len(), 3);
+            assert!(selected.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert!(selected.iter().all(|&i| i < scores.len()));
+        }
+        assert_eq!(
+            Selection::Truncation.select(&scores, 3, &mut rng),
+            vec![0, 1, 2]
+        );
+        for selection in selections.iter() {
+            assert!(selection.select(&[], 3, &mut rng).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_selection_pressure() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let scores = [3.0, 1.0, 0.0];
+        let shares = |selection: Selection, rng: &mut RandomNumberGenerator| {
+            let selected = selection.select(&scores, 4000, rng);
+            [0, 1, 2].map(|i| selected.iter().filter(|&&j| j == i).count() as f64 / 4000.0)
+        };
+        let expected = [
+            // the best of two contestants is the first one unless both are worse
+            (Selection::Tournament(2), [5.0 / 9.0, 3.0 / 9.0, 1.0 / 9.0]),
+            (Selection::RouletteWheel, [0.75, 0.25, 0.0]),
+            (Selection::StochasticUniversalSampling, [0.75, 0.25, 0.0]),
+            (Selection::LinearRanking(2.0), [2.0 / 3.0, 1.0 / 3.0, 0.0]),
+            (Selection::LinearRanking(1.0), [1.0 / 3.0; 3]),
+        ];
+        for (selection, expected) in expected {
+            for (share, expected) in shares(selection, &mut rng).iter().zip(expected) {
+                assert!((share - expected).abs() < 0.03, "{:?}", selection);
+            }
+        }
+    }
+
+    #[test]
+    fn test_infinite_scores() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        for selection in [
+            Selection::RouletteWheel,
+            Selection::StochasticUniversalSampling,
+        ] {
+            // an exact hit scores +inf and takes all of the weight
+            let selected = selection.select(&[f64::INFINITY, 1.0, 0.0], 100, &mut rng);
+            assert!(selected.iter().all(|&i| i == 0), "{:?}", selection);
+            let selected = selection.select(&[3.0, 1.0, f64::NEG_INFINITY], 100, &mut rng);
+            assert!(selected.iter().all(|&i| i < 2), "{:?}", selection);
+            assert!(selected.contains(&1), "{:?}", selection);
+            // the extreme finite scores neither overflow nor lose their proportions
+            let selected = selection.select(&[f64::MAX, 0.0, f64::MIN], 3000, &mut rng);
+            let share = selected.iter().filter(|&&i| i == 0).count() as f64 / 3000.0;
+            assert!((share - 2.0 / 3.0).abs() < 0.03, "{:?}", selection);
+            assert!(!selected.contains(&2), "{:?}", selection);
+        }
+    }
+}
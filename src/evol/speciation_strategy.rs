@@ -0,0 +1,506 @@
+use std::cell::RefCell;
+
+use super::{
+    checkpoint::Checkpointable,
+    error::EvolutionError,
+    evol_coordinator::EvolutionCoordinator,
+    evol_launcher::Fitness,
+    generation_model::GenerationModel,
+    rand::RandomNumberGenerator,
+    selection::SelectionOperator,
+    traits::{EvolutionOptionsTrait, EvolutionStrategy, Phenotype, PhenotypeDistance},
+};
+
+// keeps its own population of num_children members and clusters it into species every generation: a
+// member joins the first species whose best member is closer than the compatibility threshold, the
+// members of a species share their score, and every species breeds offspring in proportion to its
+// shared score from its own members only; the parents selected by the launcher, or the whole initial
+// population of a run started from one, are only used to start, afterwards every species selects its own
+// parents with the selection of the options by the scores its members share with the members near them,
+// and the num_parents of
+// the options are split among the species like the offspring
+pub struct SpeciationStrategy<Pheno: Phenotype> {
+    compatibility_threshold: f64,
+    state: RefCell<Option<SpeciationState<Pheno>>>,
+}
+
+struct SpeciationState<Pheno: Phenotype> {
+    population: Vec<Pheno>,
+    scores: Vec<f64>, // empty until the population has been scored
+}
+
+impl<Pheno> SpeciationStrategy<Pheno>
+where
+    Pheno: Phenotype + PhenotypeDistance,
+{
+    pub fn new(compatibility_threshold: f64) -> Self {
+        Self {
+            compatibility_threshold,
+            state: RefCell::new(None),
+        }
+    }
+
+    // the species of the last scored generation, every species and the species themselves best first
+    pub fn get_species(&self) -> Vec<Vec<Fitness<Pheno>>> {
+        let state = self.state.borrow();
+        let Some(state) = state.as_ref().filter(|state| !state.scores.is_empty()) else {
+            return Vec::new();
+        };
+        self.speciate(&state.population, &state.scores)
+            .into_iter()
+            .map(|species| {
+                species
+                    .into_iter()
+                    .map(|i| Fitness::new(state.population[i].clone(), state.scores[i]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // the niche count of every member, 1 for itself plus 1 - distance / compatibility threshold for every
+    // other member closer than the threshold, so that crowded members share their score with more others
+    fn niche_counts(&self, population: &[Pheno]) -> Vec<f64> {
+        population
+            .iter()
+            .enumerate()
+            .map(|(i, member)| {
+                1.0 + population
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, other)| member.distance(other))
+                    .filter(|&distance| distance < self.compatibility_threshold)
+                    .map(|distance| 1.0 - distance / self.compatibility_threshold)
+                    .sum::<f64>()
+            })
+            .collect()
+    }
+
+    // the indices of the members of every species, the best member of a species comes first
+    fn speciate(&self, population: &[Pheno], scores: &[f64]) -> Vec<Vec<usize>> {
+        let mut ranking: Vec<usize> = (0..population.len()).collect();
+        ranking.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        let mut species: Vec<Vec<usize>> = Vec::new();
+        for i in ranking {
+            match species.iter_mut().find(|members| {
+                population[members[0]].distance(&population[i]) < self.compatibility_threshold
+            }) {
+                Some(members) => members.push(i),
+                None => species.push(vec![i]),
+            }
+        }
+        species
+    }
+}
+
+// splits num_children among the species in proportion to their mean score above the lowest score,
+// which is the sum of their shared scores, non finite scores count as the lowest score
+fn allocate_offspring(species: &[Vec<usize>], scores: &[f64], num_children: usize) -> Vec<usize> {
+    let lowest = scores
+        .iter()
+        .cloned()
+        .filter(|score| score.is_finite())
+        .fold(f64::INFINITY, f64::min);
+    let shared: Vec<f64> = species
+        .iter()
+        .map(|members| {
+            let adjusted: f64 = members
+                .iter()
+                .map(|&i| scores[i])
+                .filter(|score| score.is_finite())
+                .map(|score| score - lowest)
+                .sum();
+            adjusted / members.len() as f64
+        })
+        .collect();
+    let total: f64 = shared.iter().sum();
+    let quotas: Vec<f64> = shared
+        .iter()
+        .map(|&shared| {
+            if total > 0.0 {
+                shared / total * num_children as f64
+            } else {
+                num_children as f64 / species.len() as f64
+            }
+        })
+        .collect();
+    let mut offspring: Vec<usize> = quotas.iter().map(|quota| *quota as usize).collect();
+    // the largest remainders receive the children lost to rounding down
+    let mut remainders: Vec<usize> = (0..species.len()).collect();
+    remainders.sort_by(|&a, &b| (quotas[b].fract()).total_cmp(&quotas[a].fract()));
+    let num_missing = num_children.saturating_sub(offspring.iter().sum());
+    for &i in remainders.iter().cycle().take(num_missing) {
+        offspring[i] += 1;
+    }
+    offspring
+}
+
+// selects the parents of a species by the shared scores of its members, their scores above the lowest
+// score of the species divided by their niche counts; the species receives the share of num_parents that
+// it breeds of num_children and at least one parent
+fn select_parents<EvolOptions: EvolutionOptionsTrait>(
+    members: &[usize],
+    scores: &[f64],
+    niche_counts: &[f64],
+    num_offspring: usize,
+    evol_options: &EvolOptions,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<usize> {
+    let lowest = members
+        .iter()
+        .map(|&i| scores[i])
+        .filter(|score| score.is_finite())
+        .fold(f64::INFINITY, f64::min);
+    let lowest = if lowest.is_finite() { lowest } else { 0.0 };
+    let shared_scores: Vec<f64> = members
+        .iter()
+        .map(|&i| (scores[i] - lowest) / niche_counts[i])
+        .collect();
+    // the selection expects the members sorted best first
+    let mut ranking: Vec<usize> = (0..members.len()).collect();
+    ranking.sort_by(|&a, &b| shared_scores[b].total_cmp(&shared_scores[a]));
+    let ranked_scores: Vec<f64> = ranking.iter().map(|&i| shared_scores[i]).collect();
+    let num_parents = (evol_options.get_num_parents() * num_offspring)
+        .div_ceil(evol_options.get_num_children().max(1))
+        .max(1);
+    evol_options
+        .get_selection()
+        .select(&ranked_scores, num_parents, rng)
+        .into_iter()
+        .map(|i| members[ranking[i]])
+        .collect()
+}
+
+impl<Pheno, EvolOptions> EvolutionStrategy<Pheno, EvolOptions> for SpeciationStrategy<Pheno>
+where
+    Pheno: Phenotype + PhenotypeDistance,
+    EvolOptions: EvolutionOptionsTrait,
+{
+    fn breed(
+        &self,
+        parents: Vec<Pheno>,
+        rng: &mut RandomNumberGenerator,
+        evol_coordinator: EvolutionCoordinator,
+        evol_options: &EvolOptions,
+    ) -> Result<Vec<Pheno>, EvolutionError> {
+        // the population is replaced by the offspring of all species every generation
+        if evol_options.get_generation_model() != GenerationModel::Generational {
+            return Err(EvolutionError::InvalidOptions(
+                "speciation needs generational runs".to_string(),
+            ));
+        }
+        let num_children = evol_options.get_num_children().max(1);
+        let mut state = self.state.borrow_mut();
+        let scored = state.as_ref().is_some_and(|state| !state.scores.is_empty());
+        if !scored {
+            // the parents, usually the starting value alone, are spread out by mutation
+            let mut population = parents.clone();
+            while population.len() < num_children {
+                let mut member = parents[rng.fetch_index(parents.len())].clone();
+                member.mutate(rng, evol_coordinator);
+                population.push(member);
+            }
+            population.truncate(num_children);
+            *state = Some(SpeciationState {
+                population: population.clone(),
+                scores: Vec::new(),
+            });
+            return Ok(population);
+        }
+        let state = state.as_mut().expect("the state was scored");
+        let species = self.speciate(&state.population, &state.scores);
+        let offspring = allocate_offspring(&species, &state.scores, num_children);
+        let niche_counts = self.niche_counts(&state.population);
+        let mut children = Vec::with_capacity(num_children);
+        for (members, num_offspring) in species.iter().zip(offspring) {
+            if num_offspring == 0 {
+                continue;
+            }
+            // the best member of every surviving species is kept unchanged
+            children.push(state.population[members[0]].clone());
+            if num_offspring == 1 {
+                continue;
+            }
+            let parents = select_parents(
+                members,
+                &state.scores,
+                &niche_counts,
+                num_offspring,
+                evol_options,
+                rng,
+            );
+            for _ in 1..num_offspring {
+                let mut child = state.population[parents[rng.fetch_index(parents.len())]].clone();
+                if parents.len() > 1 {
+                    child.crossover(&state.population[parents[rng.fetch_index(parents.len())]]);
+                }
+                child.mutate(rng, evol_coordinator);
+                children.push(child);
+            }
+        }
+        state.population = children.clone();
+        state.scores.clear();
+        Ok(children)
+    }
+
+    // the initial population becomes the population with its scores, whatever its size
+    fn start_run(&self, population: &[Pheno], scores: &[f64]) {
+        self.state
+            .replace((!population.is_empty()).then(|| SpeciationState {
+                population: population.to_vec(),
+                scores: scores.to_vec(),
+            }));
+    }
+
+    fn record_scores(&self, _candidates: &[Pheno], scores: &[f64]) -> Result<(), EvolutionError> {
+        if let Some(state) = self.state.borrow_mut().as_mut() {
+            state.scores = scores.to_vec();
+        }
+        Ok(())
+    }
+
+    fn needs_scores(&self) -> bool {
+        true
+    }
+
+    // the scores on the first line, empty until the population has been scored, followed by a line per
+    // member
+    fn save_state(&self) -> Option<String>
+    where
+        Pheno: Checkpointable,
+    {
+        let state = self.state.borrow();
+        let state = state.as_ref()?;
+        let scores = state
+            .scores
+            .iter()
+            .map(|score| score.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        let members = state.population.iter().map(Pheno::to_checkpoint);
+        Some(
+            [scores]
+                .into_iter()
+                .chain(members)
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+    }
+
+    fn load_state(&self, state: Option<&str>) -> Option<()>
+    where
+        Pheno: Checkpointable,
+    {
+        let mut lines = state?.split('\n');
+        let scores = lines
+            .next()?
+            .split(' ')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        let population = lines
+            .map(Pheno::from_checkpoint)
+            .collect::<Option<Vec<Pheno>>>()?;
+        if population.is_empty() || (!scores.is_empty() && scores.len() != population.len()) {
+            return None;
+        }
+        self.state
+            .replace(Some(SpeciationState { population, scores }));
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evol::{
+        error::EvolutionError,
+        evol_launcher::EvolutionLauncher,
+        evol_options::EvolutionOptions,
+        generation_model::{GenerationModel, Replacement},
+        genomes::real_vector::RealVector,
+        initialisation::{Initialisation, Sampling},
+        observer::{EvolutionObserver, GenerationReport, ObserverAction},
+        rand::RandomNumberGenerator,
+        selection::Selection,
+        termination::StopReason,
+        test_evol::XCoordinate,
+        traits::RealVectorPhenotype,
+    };
+
+    use super::{allocate_offspring, select_parents, SpeciationStrategy};
+
+    struct StopObserver {
+        generation: usize,
+    }
+
+    impl EvolutionObserver<XCoordinate> for StopObserver {
+        fn on_generation(&mut self, report: &GenerationReport<XCoordinate>) -> ObserverAction {
+            if report.generation == self.generation {
+                return ObserverAction::Stop;
+            }
+            ObserverAction::Continue
+        }
+    }
+
+    // two equally high peaks at -3 and 3
+    fn two_peaks(phenotype: &XCoordinate) -> f64 {
+        let x = phenotype.x();
+        -((x - 3.0) * (x - 3.0)).min((x + 3.0) * (x + 3.0))
+    }
+
+    #[test]
+    fn test_allocate_offspring() {
+        let species = vec![vec![0, 1], vec![2], vec![3]];
+        // the mean scores above the lowest score are 3, 1 and 0
+        let offspring = allocate_offspring(&species, &[4.0, 2.0, 1.0, 0.0], 10);
+        assert_eq!(offspring, vec![8, 2, 0]);
+        assert_eq!(allocate_offspring(&species, &[1.0; 4], 10), vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn test_select_parents() {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let members = [2, 0, 3];
+        let scores = [2.0, 0.0, 3.0, 1.0];
+        let alone = [1.0; 4];
+        // the species breeds half of the children and receives half of the parents
+        let evol_options = EvolutionOptions::new()
+            .with_num_parents(4)
+            .with_num_children(8);
+        let parents = select_parents(&members, &scores, &alone, 4, &evol_options, &mut rng);
+        assert_eq!(parents, vec![2, 0]);
+        // the best member is crowded, its score of 2 above the lowest one is shared to 0.5
+        let crowded = [1.0, 1.0, 4.0, 1.0];
+        let parents = select_parents(&members, &scores, &crowded, 4, &evol_options, &mut rng);
+        assert_eq!(parents, vec![0, 2]);
+        let evol_options = evol_options.with_selection(Selection::Tournament(2));
+        for _ in 0..100 {
+            let parents = select_parents(&members, &scores, &alone, 4, &evol_options, &mut rng);
+            assert_eq!(parents.len(), 2);
+            assert!(parents.iter().all(|parent| members.contains(parent)));
+        }
+        // every species has at least one parent
+        let parents = select_parents(&members, &scores, &alone, 1, &evol_options, &mut rng);
+        assert_eq!(parents.len(), 1);
+    }
+
+    #[test]
+    fn test_niche_counts() {
+        let strategy = SpeciationStrategy::new(1.0);
+        let population = [0.0, 0.5, 0.75, 3.0].map(XCoordinate::new);
+        assert_eq!(
+            strategy.niche_counts(&population),
+            vec![1.5 + 0.25, 1.0 + 0.5 + 0.75, 1.0 + 0.25 + 0.75, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_two_peaks() {
+        // the starting value lies in the valley between the peaks
+        let strategy = SpeciationStrategy::new(1.0);
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, SpeciationStrategy<_>> =
+            EvolutionLauncher::new(strategy, Box::new(two_peaks));
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        launcher
+            .evolve(
+                EvolutionOptions::new().with_num_children(40),
+                XCoordinate::new(0.0),
+                &mut rng,
+            )
+            .unwrap();
+        let champions: Vec<f64> = launcher
+            .get_strategy()
+            .get_species()
+            .iter()
+            .map(|species| species[0].phenotype.x())
+            .collect();
+        assert!(champions.iter().any(|x| (x - 3.0).abs() < 0.1));
+        assert!(champions.iter().any(|x| (x + 3.0).abs() < 0.1));
+    }
+
+    #[test]
+    fn test_initial_population() {
+        let score = |phenotype: &RealVector| {
+            let x = phenotype.get_values()[0];
+            -((x - 3.0) * (x - 3.0)).min((x + 3.0) * (x + 3.0))
+        };
+        let launcher: EvolutionLauncher<RealVector, EvolutionOptions, SpeciationStrategy<_>> =
+            EvolutionLauncher::new(SpeciationStrategy::new(1.0), Box::new(score));
+        let template = RealVector::new(vec![0.0], vec![(-5.0, 5.0)]);
+        let seeds = vec![
+            RealVector::new(vec![3.0], vec![(-5.0, 5.0)]),
+            RealVector::new(vec![-3.0], vec![(-5.0, 5.0)]),
+        ];
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        // the run stops at the initial population, the strategy holds all of it and not only the parents
+        launcher
+            .evolve_initialised(
+                EvolutionOptions::new().with_num_generations(0),
+                &template,
+                &Initialisation::new(20, Sampling::LatinHypercube).with_seeds(seeds),
+                &mut rng,
+            )
+            .unwrap();
+        let species = launcher.get_strategy().get_species();
+        assert_eq!(species.iter().map(Vec::len).sum::<usize>(), 20);
+        let champions: Vec<f64> = species
+            .iter()
+            .map(|species| species[0].phenotype.get_values()[0])
+            .collect();
+        assert!(champions.contains(&3.0));
+        assert!(champions.contains(&-3.0));
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint_path =
+            std::env::temp_dir().join(format!("speciation_checkpoint_{}.txt", std::process::id()));
+        let new_launcher =
+            || -> EvolutionLauncher<XCoordinate, EvolutionOptions, SpeciationStrategy<_>> {
+                EvolutionLauncher::new(SpeciationStrategy::new(1.0), Box::new(two_peaks))
+            };
+        let evol_options = EvolutionOptions::new()
+            .with_num_generations(40)
+            .with_num_children(20);
+        let expected = new_launcher()
+            .evolve(
+                evol_options,
+                XCoordinate::new(0.0),
+                &mut RandomNumberGenerator::from_seed(0),
+            )
+            .unwrap();
+        // the run is cancelled after the checkpoint of generation 20 and resumed by a new strategy
+        let mut launcher = new_launcher();
+        launcher.add_observer(Box::new(StopObserver { generation: 20 }));
+        let cancelled = launcher
+            .evolve_with_checkpoints(
+                evol_options,
+                XCoordinate::new(0.0),
+                &mut RandomNumberGenerator::from_seed(0),
+                &checkpoint_path,
+                10,
+            )
+            .unwrap();
+        assert_eq!(cancelled.stop_reason, StopReason::Cancelled);
+        let resumed = new_launcher();
+        let result = resumed.resume(&checkpoint_path, 10).unwrap();
+        std::fs::remove_file(&checkpoint_path).unwrap();
+        assert_eq!(result.stop_reason, StopReason::MaxGenerations);
+        assert_eq!(result.winner.x(), expected.winner.x());
+        assert_eq!(result.score, expected.score);
+    }
+
+    #[test]
+    fn test_steady_state() {
+        let launcher: EvolutionLauncher<XCoordinate, EvolutionOptions, SpeciationStrategy<_>> =
+            EvolutionLauncher::new(SpeciationStrategy::new(1.0), Box::new(two_peaks));
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        let evol_options =
+            EvolutionOptions::new().with_generation_model(GenerationModel::SteadyState {
+                num_offspring: 1,
+                replacement: Replacement::Worst,
+            });
+        let result = launcher.evolve(evol_options, XCoordinate::new(0.0), &mut rng);
+        assert!(matches!(result, Err(EvolutionError::InvalidOptions(_))));
+    }
+}
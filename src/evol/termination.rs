@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use super::checkpoint::Checkpointable;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    MaxGenerations,
+    TargetScoreReached,
+    Stagnation,
+    LowScoreSpread, // the scores of a generation hardly differ, which the phenotypes still may
+    LowDiversity,   // the phenotypes of a generation hardly differ, the population converged
+    TimeBudgetExhausted,
+    MaxEvaluationsReached,
+    Cancelled, // an observer asked to stop
+}
+
+// every criterion is optional, the run stops as soon as the first one that is set is met
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TerminationCriteria {
+    target_score: Option<f64>,      // the best score that ends the run
+    max_stagnation: Option<usize>,  // the number of generations without a better best score
+    min_score_spread: Option<f64>,  // the lowest standard deviation of the scores of a generation
+    min_diversity: Option<f64>, // the lowest mean distance between the phenotypes of a generation
+    time_budget: Option<Duration>, // the longest wall clock time of the run
+    max_evaluations: Option<usize>, // the largest number of calls to the score function
+}
+
+impl TerminationCriteria {
+    pub fn new() -> TerminationCriteria {
+        TerminationCriteria::default()
+    }
+
+    pub fn with_target_score(mut self, target_score: f64) -> TerminationCriteria {
+        self.target_score = Some(target_score);
+        self
+    }
+
+    pub fn with_max_stagnation(mut self, max_stagnation: usize) -> TerminationCriteria {
+        self.max_stagnation = Some(max_stagnation);
+        self
+    }
+
+    pub fn with_min_score_spread(mut self, min_score_spread: f64) -> TerminationCriteria {
+        self.min_score_spread = Some(min_score_spread);
+        self
+    }
+
+    // needs a launcher that measures the diversity, see EvolutionLauncher::with_diversity_measure
+    pub fn with_min_diversity(mut self, min_diversity: f64) -> TerminationCriteria {
+        self.min_diversity = Some(min_diversity);
+        self
+    }
+
+    pub fn with_time_budget(mut self, time_budget: Duration) -> TerminationCriteria {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    pub fn with_max_evaluations(mut self, max_evaluations: usize) -> TerminationCriteria {
+        self.max_evaluations = Some(max_evaluations);
+        self
+    }
+
+    pub fn get_target_score(&self) -> Option<f64> {
+        self.target_score
+    }
+
+    pub fn get_max_stagnation(&self) -> Option<usize> {
+        self.max_stagnation
+    }
+
+    pub fn get_min_score_spread(&self) -> Option<f64> {
+        self.min_score_spread
+    }
+
+    pub fn get_min_diversity(&self) -> Option<f64> {
+        self.min_diversity
+    }
+
+    pub fn get_time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+
+    pub fn get_max_evaluations(&self) -> Option<usize> {
+        self.max_evaluations
+    }
+}
+
+impl Checkpointable for TerminationCriteria {
+    fn to_checkpoint(&self) -> String {
+        fn token<T: ToString>(value: Option<T>) -> String {
+            value.map_or("-".to_string(), |value| value.to_string())
+        }
+        format!(
+            "{},{},{},{},{},{}",
+            token(self.target_score),
+            token(self.max_stagnation),
+            token(self.min_score_spread),
+            token(self.min_diversity),
+            token(self.time_budget.map(|time_budget| time_budget.as_nanos())),
+            token(self.max_evaluations)
+        )
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        fn parse<T: std::str::FromStr>(token: Option<&str>) -> Option<Option<T>> {
+            match token? {
+                "-" => Some(None),
+                value => Some(Some(value.parse().ok()?)),
+            }
+        }
+        let mut tokens = line.split(',');
+        Some(TerminationCriteria {
+            target_score: parse(tokens.next())?,
+            max_stagnation: parse(tokens.next())?,
+            min_score_spread: parse(tokens.next())?,
+            min_diversity: parse(tokens.next())?,
+            time_budget: parse::<u64>(tokens.next())?.map(Duration::from_nanos),
+            max_evaluations: parse(tokens.next())?,
+        })
+    }
+}
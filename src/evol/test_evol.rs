@@ -1,7 +1,11 @@
 use super::{
+    checkpoint::Checkpointable,
     evol_coordinator::EvolutionCoordinator,
+    fitness_cache::fingerprint_of,
+    genomes::permutation::Permutation,
+    mutation_schedule::MutationSchedule,
     rand::RandomNumberGenerator,
-    traits::{PartialPhenotype, Phenotype},
+    traits::{Fingerprint, PartialPhenotype, Phenotype, PhenotypeDistance, RealVectorPhenotype},
 };
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -28,8 +32,13 @@ impl Phenotype for XCoordinate {
     }
 
     fn mutate(&mut self, rng: &mut RandomNumberGenerator, evol_coordinator: EvolutionCoordinator) {
+        let step_size = MutationSchedule::Linear {
+            start: 1.0,
+            end: 0.01,
+        }
+        .step_size(&evol_coordinator);
         let delta = rng.fetch_uniform(-100.0, 100.0, 1)[0] as f64;
-        self.x += delta / 100.0;
+        self.x += step_size * delta / 100.0;
     }
 
     fn to_string_internal(&self) -> String {
@@ -37,6 +46,28 @@ impl Phenotype for XCoordinate {
     }
 }
 
+impl Fingerprint for XCoordinate {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(&self.x.to_bits())
+    }
+}
+
+impl PhenotypeDistance for XCoordinate {
+    fn distance(&self, other: &Self) -> f64 {
+        (self.x - other.x).abs()
+    }
+}
+
+impl Checkpointable for XCoordinate {
+    fn to_checkpoint(&self) -> String {
+        self.x.to_string()
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        Some(XCoordinate::new(line.parse().ok()?))
+    }
+}
+
 impl PartialPhenotype for XCoordinate {
     fn magnitude(&self) -> f64 {
         self.x.abs()
@@ -52,9 +83,124 @@ impl XCoordinateChallenge {
         Self { target }
     }
 
-    pub fn score(&self, phenotype: XCoordinate) -> f64 {
+    pub fn score(&self, phenotype: &XCoordinate) -> f64 {
         let x_coordinate = phenotype.x();
         let delta = x_coordinate - self.target;
         1.0 / (delta * delta)
     }
 }
+
+#[derive(Debug, Default, Clone)]
+pub struct XCoordinates {
+    xs: Vec<f64>,
+}
+
+impl XCoordinates {
+    pub fn new(xs: Vec<f64>) -> XCoordinates {
+        XCoordinates { xs }
+    }
+
+    pub fn xs(&self) -> &Vec<f64> {
+        &self.xs
+    }
+}
+
+impl Phenotype for XCoordinates {
+    fn crossover(&mut self, other: &Self) {
+        for (x, other_x) in self.xs.iter_mut().zip(other.xs.iter()) {
+            *x = (*x + other_x) / 2.0;
+        }
+    }
+
+    fn mutate(&mut self, rng: &mut RandomNumberGenerator, _: EvolutionCoordinator) {
+        let deltas = rng.fetch_uniform(-100.0, 100.0, self.xs.len());
+        for (x, delta) in self.xs.iter_mut().zip(deltas) {
+            *x += delta as f64 / 100.0;
+        }
+    }
+
+    fn to_string_internal(&self) -> String {
+        format!("xs: {:?}", self.xs)
+    }
+}
+
+impl Fingerprint for XCoordinates {
+    fn fingerprint(&self) -> u64 {
+        let bits: Vec<u64> = self.xs.iter().map(|x| x.to_bits()).collect();
+        fingerprint_of(&bits)
+    }
+}
+
+impl Checkpointable for XCoordinates {
+    fn to_checkpoint(&self) -> String {
+        self.xs
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn from_checkpoint(line: &str) -> Option<Self> {
+        let xs = line
+            .split(' ')
+            .map(|x| x.parse().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        Some(XCoordinates::new(xs))
+    }
+}
+
+impl RealVectorPhenotype for XCoordinates {
+    fn get_values(&self) -> Vec<f64> {
+        self.xs.clone()
+    }
+
+    fn set_values(&mut self, values: &[f64]) {
+        self.xs = values.to_vec();
+    }
+}
+
+pub struct XCoordinatesChallenge {
+    targets: Vec<f64>,
+}
+
+impl XCoordinatesChallenge {
+    pub fn new(targets: Vec<f64>) -> Self {
+        Self { targets }
+    }
+
+    pub fn score(&self, phenotype: &XCoordinates) -> f64 {
+        let squared_distance: f64 = phenotype
+            .xs()
+            .iter()
+            .zip(self.targets.iter())
+            .map(|(x, target)| (x - target) * (x - target))
+            .sum();
+        1.0 / squared_distance
+    }
+}
+
+// a closed tour through all cities, shorter tours score higher
+pub struct TravellingSalesmanChallenge {
+    cities: Vec<(f64, f64)>,
+}
+
+impl TravellingSalesmanChallenge {
+    pub fn new(cities: Vec<(f64, f64)>) -> Self {
+        Self { cities }
+    }
+
+    pub fn tour_length(&self, phenotype: &Permutation) -> f64 {
+        let order = phenotype.get_order();
+        (0..order.len())
+            .map(|i| {
+                let (x1, y1) = self.cities[order[i]];
+                let (x2, y2) = self.cities[order[(i + 1) % order.len()]];
+                ((x2 - x1) * (x2 - x1) + (y2 - y1) * (y2 - y1)).sqrt()
+            })
+            .sum()
+    }
+
+    pub fn score(&self, phenotype: &Permutation) -> f64 {
+        -self.tour_length(phenotype)
+    }
+}
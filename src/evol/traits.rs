@@ -1,4 +1,8 @@
-use super::{evol_coordinator::EvolutionCoordinator, rand::RandomNumberGenerator};
+use super::{
+    checkpoint::Checkpointable, error::EvolutionError, evol_coordinator::EvolutionCoordinator,
+    generation_model::GenerationModel, rand::RandomNumberGenerator,
+    score_policy::NonFiniteScorePolicy, selection::Selection, termination::TerminationCriteria,
+};
 
 pub trait EvolutionStrategy<Pheno, EvolOptions>
 where
@@ -11,7 +15,41 @@ where
         rng: &mut RandomNumberGenerator,
         evol_coordinator: EvolutionCoordinator,
         evol_options: &EvolOptions,
-    ) -> Vec<Pheno>;
+    ) -> Result<Vec<Pheno>, EvolutionError>;
+
+    // called when a run starts, strategies that keep state between generations drop the one of the last
+    // run; a run started from a full initial population passes it with its ranking scores, and such
+    // strategies start from the whole population instead of the parents selected from it
+    fn start_run(&self, _population: &[Pheno], _scores: &[f64]) {}
+
+    // receives the scores of the bred candidates in breeding order, before they are sorted
+    fn record_scores(&self, _candidates: &[Pheno], _scores: &[f64]) -> Result<(), EvolutionError> {
+        Ok(())
+    }
+
+    // whether the strategy learns from record_scores, launchers without a single score per candidate
+    // reject such strategies
+    fn needs_scores(&self) -> bool {
+        false
+    }
+
+    // the state the strategy keeps between generations as checkpoint lines separated by newlines,
+    // stateless strategies have none
+    fn save_state(&self) -> Option<String>
+    where
+        Pheno: Checkpointable,
+    {
+        None
+    }
+
+    // replaces the state with the one of a checkpoint; returns None if the state can not be read, or if
+    // it is missing for a strategy that keeps one, a resumed run must not start over
+    fn load_state(&self, state: Option<&str>) -> Option<()>
+    where
+        Pheno: Checkpointable,
+    {
+        state.is_none().then_some(())
+    }
 }
 
 pub trait EvolutionOptionsTrait
@@ -22,6 +60,37 @@ where
     fn get_log_level(&self) -> usize;
     fn get_num_parents(&self) -> usize;
     fn get_num_children(&self) -> usize;
+    // the same options breeding num_children children, the steady state model breeds its offspring with
+    // them
+    fn with_generation_size(&self, num_children: usize) -> Self;
+
+    // the defaults are the ones of EvolutionOptions::new, so that other options only override what they
+    // support
+
+    // only launchers created with new_parallel score on more than one thread
+    fn get_num_threads(&self) -> usize {
+        1
+    }
+
+    fn get_selection(&self) -> Selection {
+        Selection::Truncation
+    }
+
+    fn get_termination(&self) -> TerminationCriteria {
+        TerminationCriteria::new()
+    }
+
+    fn get_non_finite_score_policy(&self) -> NonFiniteScorePolicy {
+        NonFiniteScorePolicy::NanAsWorst
+    }
+
+    fn get_hall_of_fame_size(&self) -> usize {
+        10
+    }
+
+    fn get_generation_model(&self) -> GenerationModel {
+        GenerationModel::Generational
+    }
 }
 
 pub trait PartialEvolutionOptionsTrait
@@ -30,17 +99,65 @@ where
 {
     fn get_min_magnitude(&self) -> f64;
     fn get_max_magnitude(&self) -> f64;
+
+    // the defaults are the ones of PartialEvolutionOptions::new
+
+    fn get_max_attempts(&self) -> usize {
+        1000
+    }
+
+    fn get_max_mutations_per_attempt(&self) -> usize {
+        1000
+    }
 }
 
 pub trait Phenotype
 where
-    Self: Clone + Copy + Sized,
+    Self: Clone + Sized,
 {
     fn crossover(&mut self, other: &Self);
     fn mutate(&mut self, rng: &mut RandomNumberGenerator, evol_coordinator: EvolutionCoordinator);
     fn to_string_internal(&self) -> String;
 }
 
+// identifies phenotypes for the fitness cache, phenotypes with the same score function input must have
+// the same fingerprint
+pub trait Fingerprint {
+    fn fingerprint(&self) -> u64;
+}
+
+// how different two phenotypes are, for clustering them into species
+pub trait PhenotypeDistance {
+    fn distance(&self, other: &Self) -> f64;
+}
+
+// a phenotype that is a point in a continuous space, used by strategies that do the arithmetic themselves
+pub trait RealVectorPhenotype
+where
+    Self: Phenotype,
+{
+    fn get_values(&self) -> Vec<f64>;
+    fn set_values(&mut self, values: &[f64]);
+}
+
+// a phenotype that can be drawn at random from its search space, self is the template that knows the
+// space, like the bounds, and passes on its operators and settings to the samples
+pub trait InitialisablePhenotype
+where
+    Self: Phenotype,
+{
+    fn sample_uniform(&self, rng: &mut RandomNumberGenerator) -> Self;
+
+    // num_samples phenotypes that cover every dimension evenly, independent uniform samples by default
+    fn sample_latin_hypercube(
+        &self,
+        num_samples: usize,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<Self> {
+        (0..num_samples).map(|_| self.sample_uniform(rng)).collect()
+    }
+}
+
 pub trait PartialPhenotype
 where
     Self: Phenotype,